@@ -27,7 +27,7 @@ pub enum EffectOperator {
     PostAssign,
 }
 
-#[derive(Serialize, Debug, Copy, Clone)]
+#[derive(Serialize, Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Object {
     Ship,
     Item(usize),
@@ -64,6 +64,9 @@ pub enum SlotType {
     Service,
     DroneBay,
     Charge,
+    Implant,
+    Booster,
+    Mode,
     None,
 }
 
@@ -96,23 +99,16 @@ impl Attribute {
 
 impl EffectCategory {
     pub fn is_active(&self) -> bool {
-        match self {
-            EffectCategory::Active | EffectCategory::Overload => true,
-            _ => false,
-        }
+        matches!(self, EffectCategory::Active | EffectCategory::Overload)
     }
 }
 
 impl Slot {
     pub fn is_module(&self) -> bool {
-        match self.r#type {
-            SlotType::High
-            | SlotType::Medium
-            | SlotType::Low
-            | SlotType::Rig
-            | SlotType::SubSystem => true,
-            _ => false,
-        }
+        matches!(
+            self.r#type,
+            SlotType::High | SlotType::Medium | SlotType::Low | SlotType::Rig | SlotType::SubSystem
+        )
     }
 }
 
@@ -164,6 +160,22 @@ impl Item {
         }
     }
 
+    /* Implants, boosters and ship modes; they have no slot index, and only passive effects matter. */
+    pub fn new_item(type_id: i32, slot_type: SlotType) -> Item {
+        Item {
+            type_id,
+            slot: Slot {
+                r#type: slot_type,
+                index: None,
+            },
+            charge: None,
+            state: EffectCategory::Active,
+            max_state: EffectCategory::Passive,
+            attributes: BTreeMap::new(),
+            effects: Vec::new(),
+        }
+    }
+
     pub fn new_fake(type_id: i32) -> Item {
         Item {
             type_id,
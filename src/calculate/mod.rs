@@ -1,4 +1,5 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 pub mod item;
 mod pass_1;
@@ -6,10 +7,11 @@ mod pass_2;
 mod pass_3;
 mod pass_4;
 
+use crate::data_types::EsfFit;
 use crate::info::Info;
-use item::Item;
+use item::{EffectCategory, Item, Object};
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct DamageProfile {
     pub em: f64,
     pub explosive: f64,
@@ -17,6 +19,25 @@ pub struct DamageProfile {
     pub thermal: f64,
 }
 
+/* Everything that is specific to a single calculation; the static data comes from Info. */
+#[derive(Deserialize, Debug)]
+pub struct Input {
+    pub fit: EsfFit,
+    pub skills: BTreeMap<i32, i32>,
+    #[serde(default)]
+    pub damage_profile: DamageProfile,
+}
+
+/* An effect of an item that doesn't apply, as another item stopped it. The category
+ * is that of the stopped effect; the item keeps its state, and its other effects. */
+#[derive(Serialize, Debug)]
+pub struct StoppedEffect {
+    pub item: Object,
+    pub effect_id: i32,
+    pub source: Object,
+    pub category: EffectCategory,
+}
+
 #[derive(Serialize, Debug)]
 pub struct Ship {
     pub hull: Item,
@@ -27,6 +48,28 @@ pub struct Ship {
     pub target: Item,
 
     pub damage_profile: DamageProfile,
+    pub stopped_effects: Vec<StoppedEffect>,
+}
+
+impl Default for DamageProfile {
+    fn default() -> DamageProfile {
+        DamageProfile {
+            em: 0.25,
+            explosive: 0.25,
+            kinetic: 0.25,
+            thermal: 0.25,
+        }
+    }
+}
+
+impl Input {
+    pub fn new(fit: EsfFit, skills: BTreeMap<i32, i32>) -> Input {
+        Input {
+            fit,
+            skills,
+            damage_profile: DamageProfile::default(),
+        }
+    }
 }
 
 impl Ship {
@@ -38,27 +81,24 @@ impl Ship {
             char: Item::new_fake(1373),
             structure: Item::new_fake(0),
             target: Item::new_fake(0),
-            damage_profile: DamageProfile {
-                em: 0.25,
-                explosive: 0.25,
-                kinetic: 0.25,
-                thermal: 0.25,
-            },
+            damage_profile: DamageProfile::default(),
+            stopped_effects: Vec::new(),
         }
     }
 }
 
 trait Pass {
-    fn pass(info: &impl Info, ship: &mut Ship);
+    fn pass(info: &impl Info, input: &Input, ship: &mut Ship);
 }
 
-pub fn calculate(info: &impl Info) -> Ship {
-    let mut ship = Ship::new(info.fit().ship_type_id);
+pub fn calculate(info: &impl Info, input: &Input) -> Ship {
+    let mut ship = Ship::new(input.fit.ship_type_id);
+    ship.damage_profile = input.damage_profile.clone();
 
-    pass_1::PassOne::pass(info, &mut ship);
-    pass_2::PassTwo::pass(info, &mut ship);
-    pass_3::PassThree::pass(info, &mut ship);
-    pass_4::PassFour::pass(info, &mut ship);
+    pass_1::PassOne::pass(info, input, &mut ship);
+    pass_2::PassTwo::pass(info, input, &mut ship);
+    pass_3::PassThree::pass(info, input, &mut ship);
+    pass_4::PassFour::pass(info, input, &mut ship);
 
     ship
 }
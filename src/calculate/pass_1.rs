@@ -1,5 +1,5 @@
 use super::item::{Attribute, EffectCategory, Item, Slot, SlotType};
-use super::{Info, Pass, Ship};
+use super::{Info, Input, Pass, Ship};
 use crate::data_types::{EsfSlotType, EsfState};
 
 const ATTRIBUTE_MASS_ID: i32 = 4;
@@ -38,10 +38,10 @@ impl Item {
 }
 
 impl Pass for PassOne {
-    fn pass(info: &impl Info, ship: &mut Ship) {
+    fn pass(info: &impl Info, input: &Input, ship: &mut Ship) {
         ship.hull.set_attributes(info);
 
-        for (skill_id, skill_level) in info.skills() {
+        for (skill_id, skill_level) in &input.skills {
             let mut skill = Item::new_fake(*skill_id);
 
             skill.set_attributes(info);
@@ -50,7 +50,7 @@ impl Pass for PassOne {
             ship.skills.push(skill);
         }
 
-        for module in &info.fit().modules {
+        for module in &input.fit.modules {
             let state = match module.state {
                 EsfState::Passive => EffectCategory::Passive,
                 EsfState::Online => EffectCategory::Online,
@@ -76,14 +76,14 @@ impl Pass for PassOne {
             );
 
             item.set_attributes(info);
-            item.charge
-                .as_mut()
-                .map(|charge| charge.set_attributes(info));
+            if let Some(charge) = item.charge.as_mut() {
+                charge.set_attributes(info)
+            }
 
             ship.items.push(item);
         }
 
-        for drone in &info.fit().drones {
+        for drone in &input.fit.drones {
             let state = match drone.state {
                 EsfState::Passive => EffectCategory::Passive,
                 _ => EffectCategory::Active,
@@ -95,5 +95,29 @@ impl Pass for PassOne {
 
             ship.items.push(item);
         }
+
+        let implants = input
+            .fit
+            .implants
+            .iter()
+            .map(|implant| (implant.type_id, SlotType::Implant));
+        let boosters = input
+            .fit
+            .boosters
+            .iter()
+            .map(|booster| (booster.type_id, SlotType::Booster));
+        let mode = input
+            .fit
+            .mode
+            .iter()
+            .map(|mode| (mode.type_id, SlotType::Mode));
+
+        for (type_id, slot_type) in implants.chain(boosters).chain(mode) {
+            let mut item = Item::new_item(type_id, slot_type);
+
+            item.set_attributes(info);
+
+            ship.items.push(item);
+        }
     }
 }
@@ -1,7 +1,7 @@
 use crate::data_types::{DogmaEffectModifierInfoDomain, DogmaEffectModifierInfoFunc};
 
 use super::item::{Effect, EffectCategory, EffectOperator, Item, Object};
-use super::{Info, Pass, Ship};
+use super::{Info, Input, Pass, Ship, StoppedEffect};
 
 /** AttributeIDs for requiredSkill1, requiredSkill2, .. */
 const ATTRIBUTE_SKILLS: [i32; 6] = [182, 183, 184, 1285, 1289, 1290];
@@ -11,6 +11,7 @@ const EXEMPT_PENALTY_CATEGORY_IDS: [i32; 5] = [6, 8, 16, 20, 32];
 
 pub struct PassTwo {}
 
+#[allow(clippy::enum_variant_names)]
 #[derive(Debug)]
 enum Modifier {
     LocationRequiredSkillModifier(i32),
@@ -22,6 +23,7 @@ enum Modifier {
 
 #[derive(Debug)]
 struct Pass2Effect {
+    effect_id: i32,
     modifier: Modifier,
     operator: EffectOperator,
     source: Object,
@@ -31,6 +33,14 @@ struct Pass2Effect {
     target_attribute_id: i32,
 }
 
+#[derive(Debug)]
+struct Pass2Stopper {
+    source: Object,
+    source_category: EffectCategory,
+    domain: DogmaEffectModifierInfoDomain,
+    effect_id: i32,
+}
+
 fn get_modifier_func(
     func: DogmaEffectModifierInfoFunc,
     skill_type_id: Option<i32>,
@@ -48,7 +58,7 @@ fn get_modifier_func(
         DogmaEffectModifierInfoFunc::OwnerRequiredSkillModifier => {
             Some(Modifier::OwnerRequiredSkillModifier(skill_type_id.unwrap()))
         }
-        /* EffectStopper has no effect on the attributes; just on what you can bring online. */
+        /* EffectStopper has no effect on the attributes; it is handled by apply_effect_stoppers(). */
         DogmaEffectModifierInfoFunc::EffectStopper => None,
     }
 }
@@ -132,6 +142,7 @@ impl Item {
         info: &impl Info,
         origin: Object,
         effects: &mut Vec<Pass2Effect>,
+        stoppers: &mut Vec<Pass2Stopper>,
     ) {
         for dogma_effect in info.get_dogma_effects(self.type_id) {
             let type_dogma_effect = info.get_dogma_effect(dogma_effect.effectID);
@@ -144,6 +155,18 @@ impl Item {
 
             if !type_dogma_effect.modifierInfo.is_empty() {
                 for modifier in type_dogma_effect.modifierInfo {
+                    if let DogmaEffectModifierInfoFunc::EffectStopper = modifier.func {
+                        if let Some(effect_id) = modifier.effectID {
+                            stoppers.push(Pass2Stopper {
+                                source: origin,
+                                source_category: category,
+                                domain: modifier.domain,
+                                effect_id,
+                            });
+                        }
+                        continue;
+                    }
+
                     let effect_modifier =
                         get_modifier_func(modifier.func, modifier.skillTypeID, modifier.groupID);
                     if effect_modifier.is_none() {
@@ -156,17 +179,17 @@ impl Item {
                     }
 
                     /* If the origin is an Item(), the domain is OtherID, but there is no charge, skip the effect. */
-                    match (&origin, &modifier.domain) {
-                        (Object::Item(_), DogmaEffectModifierInfoDomain::OtherID) => {
-                            if self.charge.is_none() {
-                                continue;
-                            }
+                    if let (Object::Item(_), DogmaEffectModifierInfoDomain::OtherID) =
+                        (&origin, &modifier.domain)
+                    {
+                        if self.charge.is_none() {
+                            continue;
                         }
-                        _ => {}
                     }
 
                     let target = get_target_object(modifier.domain, origin);
                     effects.push(Pass2Effect {
+                        effect_id: dogma_effect.effectID,
                         modifier: effect_modifier.unwrap(),
                         operator: operator.unwrap(),
                         source: origin,
@@ -192,27 +215,148 @@ impl Item {
     }
 }
 
+fn object_item(ship: &Ship, object: Object) -> Option<&Item> {
+    match object {
+        Object::Ship => Some(&ship.hull),
+        Object::Item(index) => ship.items.get(index),
+        Object::Charge(index) => ship.items.get(index)?.charge.as_deref(),
+        Object::Skill(index) => ship.skills.get(index),
+        Object::Char => Some(&ship.char),
+        Object::Structure | Object::Target => None,
+    }
+}
+
+fn object_item_mut(ship: &mut Ship, object: Object) -> Option<&mut Item> {
+    match object {
+        Object::Ship => Some(&mut ship.hull),
+        Object::Item(index) => ship.items.get_mut(index),
+        Object::Charge(index) => ship.items.get_mut(index)?.charge.as_deref_mut(),
+        Object::Skill(index) => ship.skills.get_mut(index),
+        Object::Char => Some(&mut ship.char),
+        Object::Structure | Object::Target => None,
+    }
+}
+
+/* An active stopper prevents a single effect on other items of our ship; the items
+ * keep their state, and their other effects still apply. For the ItemID, ShipID and
+ * CharID domains the effect is stopped on the hull and every module, drone and charge;
+ * for OtherID only on the charge of the module (or the module of the charge). Stoppers
+ * on the structure or target are ignored, as we don't know what is there.
+ *
+ * Whether a stopper is active depends only on the state of its source, which stopping
+ * effects doesn't change; so the order of the stoppers doesn't matter. */
+fn find_stopped_effects(
+    info: &impl Info,
+    ship: &Ship,
+    stoppers: &[Pass2Stopper],
+) -> Vec<StoppedEffect> {
+    let mut stopped_effects = Vec::new();
+
+    for stopper in stoppers {
+        let Some(source) = object_item(ship, stopper.source) else {
+            continue;
+        };
+        if stopper.source_category > source.state {
+            continue;
+        }
+
+        let targets = match (&stopper.domain, stopper.source) {
+            (
+                DogmaEffectModifierInfoDomain::ItemID
+                | DogmaEffectModifierInfoDomain::ShipID
+                | DogmaEffectModifierInfoDomain::CharID,
+                _,
+            ) => {
+                let mut targets = vec![Object::Ship];
+                for (index, item) in ship.items.iter().enumerate() {
+                    targets.push(Object::Item(index));
+                    if item.charge.is_some() {
+                        targets.push(Object::Charge(index));
+                    }
+                }
+                targets.retain(|target| *target != stopper.source);
+                targets
+            }
+            (DogmaEffectModifierInfoDomain::OtherID, Object::Item(index)) => {
+                vec![Object::Charge(index)]
+            }
+            (DogmaEffectModifierInfoDomain::OtherID, Object::Charge(index)) => {
+                vec![Object::Item(index)]
+            }
+            _ => continue,
+        };
+
+        let stopped_category =
+            get_effect_category(info.get_dogma_effect(stopper.effect_id).effectCategory);
+
+        for target in targets {
+            let Some(item) = object_item(ship, target) else {
+                continue;
+            };
+
+            /* Only report effects that would otherwise have applied. */
+            if item.state < stopped_category {
+                continue;
+            }
+            if !info
+                .get_dogma_effects(item.type_id)
+                .iter()
+                .any(|effect| effect.effectID == stopper.effect_id)
+            {
+                continue;
+            }
+
+            stopped_effects.push(StoppedEffect {
+                item: target,
+                effect_id: stopper.effect_id,
+                source: stopper.source,
+                category: stopped_category,
+            });
+        }
+    }
+
+    stopped_effects
+}
+
 impl Pass for PassTwo {
-    fn pass(info: &impl Info, ship: &mut Ship) {
+    fn pass(info: &impl Info, _input: &Input, ship: &mut Ship) {
         let mut effects = Vec::new();
+        let mut stoppers = Vec::new();
 
         /* Collect all the effects in a single list. */
-        ship.hull.collect_effects(info, Object::Ship, &mut effects);
-        ship.char.collect_effects(info, Object::Char, &mut effects);
+        ship.hull
+            .collect_effects(info, Object::Ship, &mut effects, &mut stoppers);
+        ship.char
+            .collect_effects(info, Object::Char, &mut effects, &mut stoppers);
         for (index, item) in ship.items.iter_mut().enumerate() {
-            item.collect_effects(info, Object::Item(index), &mut effects);
+            item.collect_effects(info, Object::Item(index), &mut effects, &mut stoppers);
             if let Some(charge) = &mut item.charge {
-                charge.collect_effects(info, Object::Charge(index), &mut effects);
+                charge.collect_effects(info, Object::Charge(index), &mut effects, &mut stoppers);
             }
         }
         for (index, skill) in ship.skills.iter_mut().enumerate() {
-            skill.collect_effects(info, Object::Skill(index), &mut effects);
+            skill.collect_effects(info, Object::Skill(index), &mut effects, &mut stoppers);
+        }
+
+        /* Drop the stopped effects, before they are applied to any attribute. */
+        let stopped_effects = find_stopped_effects(info, ship, &stoppers);
+        effects.retain(|effect| {
+            !stopped_effects.iter().any(|stopped_effect| {
+                stopped_effect.item == effect.source && stopped_effect.effect_id == effect.effect_id
+            })
+        });
+        for stopped_effect in &stopped_effects {
+            if let Some(item) = object_item_mut(ship, stopped_effect.item) {
+                item.effects
+                    .retain(|effect_id| *effect_id != stopped_effect.effect_id);
+            }
         }
+        ship.stopped_effects = stopped_effects;
 
         /* Depending on the modifier, move the effects to the correct attribute. */
         for effect in effects {
             let source_type_id = match effect.source {
-                Object::Ship => info.fit().ship_type_id,
+                Object::Ship => ship.hull.type_id,
                 Object::Item(index) => ship.items[index].type_id,
                 Object::Charge(index) => ship.items[index].charge.as_ref().unwrap().type_id,
                 Object::Skill(index) => ship.skills[index].type_id,
@@ -1,5 +1,5 @@
 use super::item::Attribute;
-use super::{Info, Item, Pass, Ship};
+use super::{Info, Input, Item, Pass, Ship};
 
 pub struct PassFour {}
 
@@ -9,13 +9,13 @@ impl Item {
     pub fn add_attribute(&mut self, attribute_id: i32, base_value: f64, value: f64) {
         let mut attribute = Attribute::new(base_value);
         attribute.value = Some(value);
-        self.attributes.insert(attribute_id as i32, attribute);
+        self.attributes.insert(attribute_id, attribute);
     }
 }
 
 /* Attributes don't contain all information displayed, so we calculate some fake attributes with those values. */
 impl Pass for PassFour {
-    fn pass(info: &impl Info, ship: &mut Ship) {
+    fn pass(info: &impl Info, _input: &Input, ship: &mut Ship) {
         capacitor::attribute_capacitor_depletes_in(info, ship);
     }
 }
@@ -0,0 +1,191 @@
+use serde::Serialize;
+
+use esf_dogma_engine::calculate::item::Object;
+use esf_dogma_engine::calculate::{self, Ship};
+use esf_dogma_engine::info::{InfoName, InfoSkills};
+
+use super::fit::{apply_state, load_skills, read_fits, FitArgs, SkillArgs};
+use super::output::OutputFormat;
+use super::{object_item, object_matches, object_name, report_skipped, ship_objects};
+
+#[derive(clap::Args)]
+pub struct AttributesArgs {
+    #[clap(flatten)]
+    pub fit: FitArgs,
+
+    #[clap(flatten)]
+    pub skills: SkillArgs,
+
+    /// State of the modules; 8 letters for each high/medium/low slot. P = Passive (Offline), O = Online, A = Active, V = Overload.
+    #[clap(short, long)]
+    pub state: Option<String>,
+
+    /// Only show the attributes of this item: "ship", "char", or the name of a module, charge, drone, implant or booster [default: all items].
+    #[clap(short, long)]
+    pub item: Option<String>,
+
+    /// Only show attributes whose name matches this pattern; case-insensitive, "*" matches anything (like "*range*") [default: part of the name].
+    #[clap(short, long)]
+    pub name: Option<String>,
+
+    /// Format to print the attributes in; JSON is one line per attribute.
+    #[clap(short, long, value_enum, default_value_t = OutputFormat::Table)]
+    pub output: OutputFormat,
+}
+
+#[derive(Serialize)]
+struct AttributeLine<'a> {
+    fit: &'a str,
+    item: &'a str,
+    attribute_id: i32,
+    name: &'a str,
+    base_value: f64,
+    value: f64,
+    effects: usize,
+}
+
+/* Match a name against a pattern where "*" matches any (possibly empty) text.
+ * Without a "*", the pattern matches any name containing it. */
+fn matches_pattern(name: &str, pattern: &str) -> bool {
+    let name = name.to_lowercase();
+    let pattern = pattern.to_lowercase();
+    if !pattern.contains('*') {
+        return name.contains(&pattern);
+    }
+
+    let parts = pattern.split('*').collect::<Vec<_>>();
+    let (first, last) = (parts[0], parts[parts.len() - 1]);
+    if !name.starts_with(first) || !name[first.len()..].ends_with(last) {
+        return false;
+    }
+
+    let mut rest = &name[first.len()..name.len() - last.len()];
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(position) => rest = &rest[position + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+/* Print every attribute of the object (filtered by name pattern) with its base
+ * value, final value and the number of effects modifying it. */
+fn print_attributes(
+    info: &impl InfoName,
+    ship: &Ship,
+    object: &Object,
+    fit_name: &str,
+    args: &AttributesArgs,
+) {
+    let item = object_item(ship, object).unwrap();
+    let item_name = object_name(info, ship, object);
+
+    let attributes = item
+        .attributes
+        .iter()
+        .map(|(attribute_id, attribute)| {
+            let name = info
+                .attribute_id_to_name(*attribute_id)
+                .unwrap_or_else(|| attribute_id.to_string());
+            (*attribute_id, name, attribute)
+        })
+        .filter(|(_, name, _)| {
+            args.name
+                .as_deref()
+                .is_none_or(|pattern| matches_pattern(name, pattern))
+        })
+        .collect::<Vec<_>>();
+    if attributes.is_empty() {
+        return;
+    }
+
+    if args.output == OutputFormat::Table {
+        println!("[{}] {}", fit_name, item_name);
+        println!(
+            "  {:>6}  {:<40}{:>16}{:>16}{:>9}",
+            "ID", "Name", "Base", "Final", "Effects"
+        );
+    }
+
+    for (attribute_id, name, attribute) in attributes {
+        let value = attribute.value.unwrap_or(attribute.base_value);
+
+        match args.output {
+            OutputFormat::Json => {
+                let line = AttributeLine {
+                    fit: fit_name,
+                    item: &item_name,
+                    attribute_id,
+                    name: &name,
+                    base_value: attribute.base_value,
+                    value,
+                    effects: attribute.effects.len(),
+                };
+                println!("{}", serde_json::to_string(&line).unwrap());
+            }
+            OutputFormat::Table => println!(
+                "  {:>6}  {:<40}{:>16}{:>16}{:>9}",
+                attribute_id,
+                name,
+                attribute.base_value,
+                value,
+                attribute.effects.len()
+            ),
+        }
+    }
+
+    if args.output == OutputFormat::Table {
+        println!();
+    }
+}
+
+pub fn run(info: &(impl InfoName + InfoSkills), args: AttributesArgs) -> Result<(), String> {
+    let skills = load_skills(info, &args.skills)?;
+    let fits = read_fits(info, &args.fit)?;
+
+    let mut errors = Vec::new();
+    for (index, fit) in fits.into_iter().enumerate() {
+        let fit = match fit {
+            Ok(fit) => fit,
+            Err(error) => {
+                errors.push(format!("Fit {}: {}", index + 1, error));
+                continue;
+            }
+        };
+        report_skipped(&fit);
+
+        let mut esf_fit = fit.esf_fit;
+        if let Some(state) = &args.state {
+            apply_state(&mut esf_fit, state)?;
+        }
+        let input = calculate::Input::new(esf_fit, skills.clone());
+        let ship = calculate::calculate(info, &input);
+
+        let objects = ship_objects(&ship)
+            .into_iter()
+            .filter(|object| {
+                args.item
+                    .as_deref()
+                    .is_none_or(|item_name| object_matches(info, &ship, object, item_name))
+            })
+            .collect::<Vec<_>>();
+        if objects.is_empty() {
+            errors.push(format!(
+                "{}: no item '{}' in the fit",
+                fit.name,
+                args.item.as_deref().unwrap_or_default()
+            ));
+            continue;
+        }
+
+        for object in &objects {
+            print_attributes(info, &ship, object, &fit.name, &args);
+        }
+    }
+
+    match errors.is_empty() {
+        true => Ok(()),
+        false => Err(errors.join("\n")),
+    }
+}
@@ -0,0 +1,141 @@
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use esf_dogma_engine::calculate;
+use esf_dogma_engine::rust;
+
+use super::data::{load_data, DataArgs};
+use super::fit::{load_skills, read_fits, FitArgs, FitFormat, SkillArgs};
+use super::output::{check_statistics_attributes, output, Output, OutputFormat};
+
+#[derive(clap::Args)]
+pub struct BatchArgs {
+    /// Files with the fit(s); every fit of every file is calculated.
+    #[clap(required = true)]
+    pub filenames: Vec<PathBuf>,
+
+    /// Format of the fits [default: detected from the content of each file].
+    #[clap(long)]
+    pub from: Option<FitFormat>,
+
+    /// Skip lines of an EFT that cannot be parsed, instead of failing.
+    #[clap(short, long)]
+    pub lenient: bool,
+
+    #[clap(flatten)]
+    pub skills: SkillArgs,
+
+    /// Number of threads to calculate with [default: the number of CPUs].
+    #[clap(short, long)]
+    pub threads: Option<usize>,
+
+    /// Format to print the results in; JSON is one line per fit.
+    #[clap(short, long, value_enum, default_value_t = OutputFormat::Json)]
+    pub output: OutputFormat,
+}
+
+#[derive(Serialize)]
+struct BatchResult {
+    file: String,
+    name: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    skipped: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    output: Option<Output>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/* Calculate all fits of all files in parallel, and print the result of every fit. */
+pub fn run(data_args: &DataArgs, args: BatchArgs) -> Result<(), String> {
+    let data = load_data(data_args)?;
+    let info = rust::InfoMain::new(&data);
+    check_statistics_attributes(&info)?;
+    let skills = load_skills(&info, &args.skills)?;
+
+    /* Fits that cannot be read get their error right away; the rest is calculated. */
+    let mut results = Vec::new();
+    let mut inputs = Vec::new();
+    for filename in args.filenames {
+        let fit_args = FitArgs {
+            filename: Some(filename.clone()),
+            from: args.from,
+            lenient: args.lenient,
+        };
+        let file = filename.display().to_string();
+
+        let fits = match read_fits(&info, &fit_args) {
+            Ok(fits) => fits,
+            Err(error) => {
+                results.push(BatchResult {
+                    file,
+                    name: String::new(),
+                    skipped: Vec::new(),
+                    output: None,
+                    error: Some(error),
+                });
+                continue;
+            }
+        };
+
+        for (index, fit) in fits.into_iter().enumerate() {
+            match fit {
+                Ok(fit) => {
+                    results.push(BatchResult {
+                        file: file.clone(),
+                        name: fit.name,
+                        skipped: fit.skipped,
+                        output: None,
+                        error: None,
+                    });
+                    inputs.push((
+                        results.len() - 1,
+                        calculate::Input::new(fit.esf_fit, skills.clone()),
+                    ));
+                }
+                Err(error) => results.push(BatchResult {
+                    file: file.clone(),
+                    name: format!("Fit {}", index + 1),
+                    skipped: Vec::new(),
+                    output: None,
+                    error: Some(error),
+                }),
+            }
+        }
+    }
+
+    let (indices, inputs): (Vec<usize>, Vec<calculate::Input>) = inputs.into_iter().unzip();
+    let ships = rust::calculate_batch(&data, &inputs, args.threads);
+    for (index, ship) in indices.into_iter().zip(ships) {
+        match ship {
+            Ok(ship) => results[index].output = Some(output(&info, &ship)),
+            Err(error) => results[index].error = Some(error),
+        }
+    }
+
+    let failed = results
+        .iter()
+        .filter(|result| result.error.is_some())
+        .count();
+    for result in &results {
+        match args.output {
+            OutputFormat::Json => println!("{}", serde_json::to_string(result).unwrap()),
+            OutputFormat::Table => {
+                println!("{}: {}", result.file, result.name);
+                for skipped in &result.skipped {
+                    println!("{}", skipped);
+                }
+                match (&result.output, &result.error) {
+                    (Some(output), _) => println!("{}\n", output.to_table()),
+                    (_, error) => println!("Error: {}\n", error.as_deref().unwrap_or_default()),
+                }
+            }
+        }
+    }
+
+    match failed {
+        0 => Ok(()),
+        _ => Err(format!("{} of {} fits failed", failed, results.len())),
+    }
+}
@@ -0,0 +1,57 @@
+use esf_dogma_engine::calculate;
+use esf_dogma_engine::info::{InfoName, InfoSkills};
+
+use super::fit::{apply_state, load_skills, read_fits, FitArgs, SkillArgs};
+use super::output::{check_statistics_attributes, output, print_output, OutputFormat};
+use super::report_skipped;
+
+#[derive(clap::Args)]
+pub struct CalcArgs {
+    #[clap(flatten)]
+    pub fit: FitArgs,
+
+    #[clap(flatten)]
+    pub skills: SkillArgs,
+
+    /// State of the modules; 8 letters for each high/medium/low slot. P = Passive (Offline), O = Online, A = Active, V = Overload.
+    #[clap(short, long)]
+    pub state: Option<String>,
+
+    /// Format to print the statistics in; JSON is one line per fit.
+    #[clap(short, long, value_enum, default_value_t = OutputFormat::Json)]
+    pub output: OutputFormat,
+}
+
+/* Calculate every fit of the input, and print its statistics per fit. */
+pub fn run(info: &(impl InfoName + InfoSkills), args: CalcArgs) -> Result<(), String> {
+    check_statistics_attributes(info)?;
+    let skills = load_skills(info, &args.skills)?;
+    let fits = read_fits(info, &args.fit)?;
+
+    let mut errors = Vec::new();
+    for (index, fit) in fits.into_iter().enumerate() {
+        let (name, mut esf_fit) = match fit {
+            Ok(fit) => {
+                report_skipped(&fit);
+                (fit.name, fit.esf_fit)
+            }
+            Err(error) => {
+                errors.push(format!("Fit {}: {}", index + 1, error));
+                continue;
+            }
+        };
+
+        if let Some(state) = &args.state {
+            apply_state(&mut esf_fit, state)?;
+        }
+
+        let input = calculate::Input::new(esf_fit, skills.clone());
+        let statistics = calculate::calculate(info, &input);
+        print_output(args.output, &name, &output(info, &statistics));
+    }
+
+    match errors.is_empty() {
+        true => Ok(()),
+        false => Err(errors.join("\n")),
+    }
+}
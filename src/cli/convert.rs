@@ -0,0 +1,94 @@
+use esf_dogma_engine::dna;
+use esf_dogma_engine::eft;
+use esf_dogma_engine::esi;
+use esf_dogma_engine::info::InfoName;
+use esf_dogma_engine::xml;
+
+use super::fit::{read_fits, CliFit, FitArgs};
+use super::report_skipped;
+
+/* The formats fits can be written in; a killmail can only be read. */
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ConvertFormat {
+    Eft,
+    Dna,
+    Esi,
+    Xml,
+}
+
+#[derive(clap::Args)]
+pub struct ConvertArgs {
+    #[clap(flatten)]
+    pub fit: FitArgs,
+
+    /// Format to write the fit(s) in.
+    #[clap(long)]
+    pub to: ConvertFormat,
+}
+
+fn save_fits(
+    info: &impl InfoName,
+    fits: Vec<CliFit>,
+    format: ConvertFormat,
+) -> Result<String, String> {
+    match format {
+        ConvertFormat::Eft => {
+            let efts = fits
+                .iter()
+                .map(|fit| eft::save_esf_fit(info, &fit.name, &fit.esf_fit, &fit.cargo))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(efts.join("\n"))
+        }
+        ConvertFormat::Dna => Ok(fits
+            .iter()
+            .map(|fit| dna::save_dna(&fit.esf_fit, &fit.cargo) + "\n")
+            .collect()),
+        ConvertFormat::Esi => {
+            let esi_fittings = fits
+                .iter()
+                .map(|fit| {
+                    esi::save_esi_fitting(&fit.name, &fit.description, &fit.esf_fit, &fit.cargo)
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            /* A single fit as fitting; more fits as list of fittings. */
+            match esi_fittings.len() {
+                1 => Ok(format!("{}\n", esi_fittings[0])),
+                _ => Ok(format!("[{}]\n", esi_fittings.join(","))),
+            }
+        }
+        ConvertFormat::Xml => {
+            let xml_fits: Vec<xml::XmlFit> = fits
+                .into_iter()
+                .map(|fit| xml::XmlFit {
+                    name: fit.name,
+                    description: fit.description,
+                    esf_fit: fit.esf_fit,
+                    cargo: fit.cargo,
+                })
+                .collect();
+            xml::save_xml(info, &xml_fits)
+        }
+    }
+}
+
+/* Convert all fits of the input to another format. If any fit cannot be read, nothing is written. */
+pub fn run(info: &impl InfoName, args: ConvertArgs) -> Result<(), String> {
+    let mut fits = Vec::new();
+    let mut errors = Vec::new();
+    for (index, fit) in read_fits(info, &args.fit)?.into_iter().enumerate() {
+        match fit {
+            Ok(fit) => {
+                report_skipped(&fit);
+                fits.push(fit);
+            }
+            Err(error) => errors.push(format!("Fit {}: {}", index + 1, error)),
+        }
+    }
+    if !errors.is_empty() {
+        return Err(errors.join("\n"));
+    }
+
+    print!("{}", save_fits(info, fits, args.to)?);
+    Ok(())
+}
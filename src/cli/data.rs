@@ -0,0 +1,63 @@
+use std::path::{Path, PathBuf};
+
+use esf_dogma_engine::rust;
+
+pub const DEFAULT_PROTOBUF_LOCATION: &str = "node_modules/@eveshipfit/data/dist/sde";
+
+#[derive(clap::Args)]
+pub struct DataArgs {
+    /// Directory with the protobuf files [default: node_modules/@eveshipfit/data/dist/sde, or the embedded data if built in].
+    #[clap(short, long, global = true)]
+    pub protobuf_location: Option<PathBuf>,
+
+    /// Load the data from a bundle file, instead of the protobuf files.
+    #[clap(short, long, global = true)]
+    pub bundle: Option<PathBuf>,
+
+    /// Load the data from a compact data file, instead of the protobuf files.
+    #[clap(short, long, global = true)]
+    pub compact: Option<PathBuf>,
+
+    /// Load the data from CCP's SDE (JSONL or YAML files) in this directory, instead of the protobuf files.
+    #[clap(long, global = true)]
+    pub sde_location: Option<PathBuf>,
+}
+
+fn load_bundle(path: &Path) -> Result<rust::Data, String> {
+    let bundle =
+        std::fs::read(path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+    rust::Data::from_bundle(&bundle)
+}
+
+/* Load the data as selected by the options; the compact data file is opened by the caller, as it is not a Data. */
+pub fn load_data(args: &DataArgs) -> Result<rust::Data, String> {
+    if args.compact.is_some() {
+        return Err("This command cannot use a compact data file; use the protobuf files, a bundle or the SDE".to_string());
+    }
+    if let Some(sde_location) = &args.sde_location {
+        return rust::Data::from_sde(sde_location);
+    }
+    if let Some(bundle) = &args.bundle {
+        return load_bundle(bundle);
+    }
+
+    match &args.protobuf_location {
+        Some(protobuf_location) => rust::Data::new(protobuf_location),
+        #[cfg(feature = "embedded-data")]
+        None => rust::Data::embedded(),
+        #[cfg(not(feature = "embedded-data"))]
+        None => rust::Data::new(Path::new(DEFAULT_PROTOBUF_LOCATION)),
+    }
+}
+
+/* Load the data from a path of which the kind is detected: a bundle file, a
+ * directory with protobuf files, or a directory with CCP's SDE. */
+pub fn load_data_path(path: &Path) -> Result<rust::Data, String> {
+    if path.is_file() {
+        load_bundle(path)
+    } else if path.join("types.pb2").exists() {
+        rust::Data::new(path)
+    } else {
+        rust::Data::from_sde(path)
+    }
+}
@@ -0,0 +1,40 @@
+use std::path::PathBuf;
+
+use esf_dogma_engine::calculate;
+use esf_dogma_engine::rust;
+
+use super::data::{load_data, load_data_path, DataArgs};
+use super::fit::{load_skills, read_fits, FitArgs, SkillArgs};
+use super::report_skipped;
+
+#[derive(clap::Args)]
+pub struct DiffArgs {
+    /// Older data to compare against: a bundle file, a directory with protobuf files, or a directory with CCP's SDE.
+    pub old: PathBuf,
+
+    #[clap(flatten)]
+    pub fit: FitArgs,
+
+    #[clap(flatten)]
+    pub skills: SkillArgs,
+}
+
+/* Compare the older data with the loaded data, for the fits of the input. */
+pub fn run(data_args: &DataArgs, args: DiffArgs) -> Result<(), String> {
+    let old = load_data_path(&args.old)?;
+    let new = load_data(data_args)?;
+
+    let info = rust::InfoMain::new(&new);
+    let skills = load_skills(&info, &args.skills)?;
+
+    let mut fits = Vec::new();
+    for (index, fit) in read_fits(&info, &args.fit)?.into_iter().enumerate() {
+        let fit = fit.map_err(|error| format!("Fit {}: {}", index + 1, error))?;
+        report_skipped(&fit);
+        fits.push((fit.name, calculate::Input::new(fit.esf_fit, skills.clone())));
+    }
+
+    let data_diff = rust::diff_data(&old, &new, &fits);
+    println!("{}", serde_json::to_string(&data_diff).unwrap());
+    Ok(())
+}
@@ -0,0 +1,139 @@
+use esf_dogma_engine::calculate::item::Object;
+use esf_dogma_engine::calculate::{self, Ship};
+use esf_dogma_engine::info::{InfoName, InfoSkills};
+
+use super::fit::{apply_state, load_skills, read_fits, FitArgs, SkillArgs};
+use super::{object_item, object_matches, object_name, report_skipped, ship_objects};
+
+#[derive(clap::Args)]
+pub struct ExplainArgs {
+    /// Name (like "shieldCapacity") or ID of the attribute to explain.
+    pub attribute: String,
+
+    #[clap(flatten)]
+    pub fit: FitArgs,
+
+    #[clap(flatten)]
+    pub skills: SkillArgs,
+
+    /// State of the modules; 8 letters for each high/medium/low slot. P = Passive (Offline), O = Online, A = Active, V = Overload.
+    #[clap(short, long)]
+    pub state: Option<String>,
+
+    /// Item to explain the attribute of: "ship", "char", or the name of a module, charge, drone, implant or booster [default: ship].
+    #[clap(short, long)]
+    pub item: Option<String>,
+}
+
+/* Find the item by name; for a type fitted more than once, the first one is used. */
+fn find_item(info: &impl InfoName, ship: &Ship, name: &str) -> Option<Object> {
+    ship_objects(ship)
+        .into_iter()
+        .find(|object| object_matches(info, ship, object, name))
+}
+
+fn attribute_id(info: &impl InfoName, attribute: &str) -> Result<i32, String> {
+    if let Ok(attribute_id) = attribute.parse::<i32>() {
+        return Ok(attribute_id);
+    }
+
+    match info.attribute_name_to_id(attribute) {
+        0 => Err(format!("Unknown attribute '{}'", attribute)),
+        attribute_id => Ok(attribute_id),
+    }
+}
+
+/* Print how the value of an attribute comes to be: its base value, and every
+ * effect modifying it, with the source and the value the source contributes. */
+fn explain(info: &impl InfoName, ship: &Ship, object: &Object, attribute_id: i32) {
+    let item = object_item(ship, object).unwrap();
+    println!("{}", object_name(info, ship, object));
+
+    let Some(attribute) = item.attributes.get(&attribute_id) else {
+        let default_value = info.get_dogma_attribute(attribute_id).defaultValue;
+        println!("  Not set on this item; default value {}", default_value);
+        return;
+    };
+
+    println!("  Base value:  {}", attribute.base_value);
+    println!(
+        "  Final value: {}",
+        attribute.value.unwrap_or(attribute.base_value)
+    );
+    if attribute.effects.is_empty() {
+        println!("  Not modified by any effect");
+        return;
+    }
+
+    println!("  Modified by:");
+    for effect in &attribute.effects {
+        let source_value = object_item(ship, &effect.source)
+            .and_then(|source| source.attributes.get(&effect.source_attribute_id))
+            .map(|source_attribute| {
+                source_attribute
+                    .value
+                    .unwrap_or(source_attribute.base_value)
+            });
+        let source_attribute_name = info
+            .attribute_id_to_name(effect.source_attribute_id)
+            .unwrap_or_else(|| effect.source_attribute_id.to_string());
+
+        println!(
+            "    {:?} {} ({} of {}, {:?}){}",
+            effect.operator,
+            source_value.map_or("?".to_string(), |value| value.to_string()),
+            source_attribute_name,
+            object_name(info, ship, &effect.source),
+            effect.source_category,
+            if effect.penalty {
+                ", stacking penalized"
+            } else {
+                ""
+            }
+        );
+    }
+}
+
+pub fn run(info: &(impl InfoName + InfoSkills), args: ExplainArgs) -> Result<(), String> {
+    let attribute_id = attribute_id(info, &args.attribute)?;
+    let skills = load_skills(info, &args.skills)?;
+    let fits = read_fits(info, &args.fit)?;
+
+    let mut errors = Vec::new();
+    for (index, fit) in fits.into_iter().enumerate() {
+        let fit = match fit {
+            Ok(fit) => fit,
+            Err(error) => {
+                errors.push(format!("Fit {}: {}", index + 1, error));
+                continue;
+            }
+        };
+        report_skipped(&fit);
+
+        let mut esf_fit = fit.esf_fit;
+        if let Some(state) = &args.state {
+            apply_state(&mut esf_fit, state)?;
+        }
+        let input = calculate::Input::new(esf_fit, skills.clone());
+        let ship = calculate::calculate(info, &input);
+
+        let item_name = args.item.as_deref().unwrap_or("ship");
+        match find_item(info, &ship, item_name) {
+            Some(object) => {
+                println!(
+                    "[{}] {}",
+                    fit.name,
+                    info.attribute_id_to_name(attribute_id)
+                        .unwrap_or_else(|| attribute_id.to_string())
+                );
+                explain(info, &ship, &object, attribute_id);
+            }
+            None => errors.push(format!("{}: no item '{}' in the fit", fit.name, item_name)),
+        }
+    }
+
+    match errors.is_empty() {
+        true => Ok(()),
+        false => Err(errors.join("\n")),
+    }
+}
@@ -0,0 +1,266 @@
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::PathBuf;
+
+use clap::ValueEnum;
+
+use esf_dogma_engine::data_types::{EsfCargo, EsfFit, EsfSlotType, EsfState};
+use esf_dogma_engine::dna;
+use esf_dogma_engine::eft;
+use esf_dogma_engine::esi;
+use esf_dogma_engine::info::{InfoName, InfoSkills};
+use esf_dogma_engine::skills::{self, SkillProfile};
+use esf_dogma_engine::xml;
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum FitFormat {
+    Eft,
+    Dna,
+    Esi,
+    Killmail,
+    Xml,
+}
+
+#[derive(clap::Args)]
+pub struct FitArgs {
+    /// File with the fit(s); EFT, DNA, ESI fitting JSON, ESI killmail JSON or EVE client XML [default: stdin].
+    pub filename: Option<PathBuf>,
+
+    /// Format of the fit(s) [default: detected from the content].
+    #[clap(long)]
+    pub from: Option<FitFormat>,
+
+    /// Skip lines of an EFT that cannot be parsed, instead of failing.
+    #[clap(short, long)]
+    pub lenient: bool,
+}
+
+#[derive(clap::Args)]
+pub struct SkillArgs {
+    /// JSON file with skill levels ({"<typeID>": <level>}); skills not in the file are level 1.
+    #[clap(short = 'f', long)]
+    pub skills_filename: Option<PathBuf>,
+
+    /// Skill profile to start from (all-0, all-iv, all-v, alpha); the skills-file is applied on top.
+    #[clap(short = 'k', long)]
+    pub skill_profile: Option<SkillProfile>,
+}
+
+/* A fit read from any of the supported formats. */
+pub struct CliFit {
+    pub name: String,
+    pub description: String,
+    pub esf_fit: EsfFit,
+    pub cargo: Vec<EsfCargo>,
+    /* What was skipped while reading (lines of a lenient EFT, unknown ESI flags); it is
+     * up to the command to report these. */
+    pub skipped: Vec<String>,
+}
+
+fn detect_format(content: &str) -> FitFormat {
+    let content = content.trim_start();
+
+    if content.starts_with('<') {
+        FitFormat::Xml
+    } else if content.starts_with('{') {
+        if content.contains("\"victim\"") {
+            FitFormat::Killmail
+        } else {
+            FitFormat::Esi
+        }
+    } else if let Some(rest) = content.strip_prefix('[') {
+        /* An EFT header, or a list of ESI fittings. */
+        match rest.trim_start().starts_with('{') {
+            true => FitFormat::Esi,
+            false => FitFormat::Eft,
+        }
+    } else {
+        FitFormat::Dna
+    }
+}
+
+fn read_input(filename: &Option<PathBuf>) -> Result<String, String> {
+    match filename {
+        Some(filename) => std::fs::read_to_string(filename)
+            .map_err(|e| format!("Cannot read {}: {}", filename.display(), e)),
+        None => {
+            let mut buffer = String::new();
+            std::io::stdin()
+                .read_to_string(&mut buffer)
+                .map_err(|e| format!("Cannot read stdin: {}", e))?;
+            Ok(buffer)
+        }
+    }
+}
+
+fn read_eft(info: &impl InfoName, content: &str, lenient: bool) -> Vec<Result<CliFit, String>> {
+    let mode = match lenient {
+        true => eft::EftMode::Lenient,
+        false => eft::EftMode::Strict,
+    };
+
+    eft::parse_eft_document(info, content, mode)
+        .into_iter()
+        .map(|result| match result {
+            Ok((eft_fit, diagnostics)) => Ok(CliFit {
+                name: eft_fit.name,
+                description: String::new(),
+                esf_fit: eft_fit.esf_fit,
+                cargo: eft_fit.cargo,
+                skipped: diagnostics
+                    .iter()
+                    .map(|diagnostic| {
+                        format!("Skipped line {}: {}", diagnostic.line, diagnostic.reason)
+                    })
+                    .collect(),
+            }),
+            Err(diagnostics) => Err(diagnostics
+                .iter()
+                .map(|diagnostic| diagnostic.to_string())
+                .collect::<Vec<_>>()
+                .join("\n")),
+        })
+        .collect()
+}
+
+fn read_dna(info: &impl InfoName, content: &str) -> Vec<Result<CliFit, String>> {
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let dna_fit = dna::load_dna(info, line)?;
+            Ok(CliFit {
+                name: info
+                    .type_id_to_name(dna_fit.esf_fit.ship_type_id)
+                    .unwrap_or_default(),
+                description: String::new(),
+                esf_fit: dna_fit.esf_fit,
+                cargo: dna_fit.cargo,
+                skipped: Vec::new(),
+            })
+        })
+        .collect()
+}
+
+fn from_esi_fit(esi_fit: esi::EsiFit) -> CliFit {
+    CliFit {
+        name: esi_fit.name,
+        description: esi_fit.description,
+        esf_fit: esi_fit.esf_fit,
+        cargo: esi_fit.cargo,
+        skipped: esi_fit.skipped,
+    }
+}
+
+fn read_esi(content: &str) -> Vec<Result<CliFit, String>> {
+    /* Either a single fitting, or a list of them (as ESI returns the fittings of a character). */
+    if content.trim_start().starts_with('[') {
+        return match serde_json::from_str::<Vec<esi::EsiFitting>>(content) {
+            Ok(esi_fittings) => esi_fittings
+                .iter()
+                .map(|esi_fitting| Ok(from_esi_fit(esi_fitting.to_esf_fit())))
+                .collect(),
+            Err(e) => vec![Err(format!("Invalid ESI fittings: {}", e))],
+        };
+    }
+
+    vec![esi::load_esi_fitting(content).map(from_esi_fit)]
+}
+
+fn read_xml(info: &impl InfoName, content: &str) -> Vec<Result<CliFit, String>> {
+    xml::load_xml(info, content)
+        .into_iter()
+        .map(|result| {
+            result.map(|xml_fit| CliFit {
+                name: xml_fit.name,
+                description: xml_fit.description,
+                esf_fit: xml_fit.esf_fit,
+                cargo: xml_fit.cargo,
+                skipped: Vec::new(),
+            })
+        })
+        .collect()
+}
+
+/* Read all fits from a file (or stdin). A document can hold multiple fits; each fit
+ * either loads or has its own error, so one broken fit doesn't hide the others. */
+pub fn read_fits(
+    info: &impl InfoName,
+    args: &FitArgs,
+) -> Result<Vec<Result<CliFit, String>>, String> {
+    let content = read_input(&args.filename)?;
+
+    let fits = match args.from.unwrap_or_else(|| detect_format(&content)) {
+        FitFormat::Eft => read_eft(info, &content, args.lenient),
+        FitFormat::Dna => read_dna(info, &content),
+        FitFormat::Esi => read_esi(&content),
+        FitFormat::Killmail => vec![esi::load_killmail(info, &content).map(from_esi_fit)],
+        FitFormat::Xml => read_xml(info, &content),
+    };
+    if fits.is_empty() {
+        return Err("No fit found in the input".to_string());
+    }
+
+    Ok(fits)
+}
+
+/* Set the state of the high, medium and low slot modules from a 24 letter string;
+ * 8 per slot type. If a module is set to a state it cannot reach, it will
+ * automatically be set to a lower state during calculation. */
+pub fn apply_state(fit: &mut EsfFit, state: &str) -> Result<(), String> {
+    let state = state.chars().collect::<Vec<char>>();
+    if state.len() != 24 {
+        return Err("State should be 24 letters; 8 for each high/medium/low slot. P = Passive (Offline), O = Online, A = Active, V = Overload.".to_string());
+    }
+
+    for (slot_index, slot_type) in [EsfSlotType::High, EsfSlotType::Medium, EsfSlotType::Low]
+        .iter()
+        .enumerate()
+    {
+        for index in 0..8 {
+            let module = fit
+                .modules
+                .iter_mut()
+                .find(|module| module.slot.index == index && module.slot.r#type == *slot_type);
+            if let Some(module) = module {
+                module.state = match state[slot_index * 8 + index as usize] {
+                    'P' => EsfState::Passive,
+                    'O' => EsfState::Online,
+                    'A' => EsfState::Active,
+                    'V' => EsfState::Overload,
+                    c => return Err(format!("Invalid state character: {}", c)),
+                };
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/* Load the skills from the skills-file and / or skill profile. Be mindful:
+ * - Skills not in the list are assumed L1 (by dogma-data).
+ * - Skills injected but not trained are L0.
+ */
+pub fn load_skills(info: &impl InfoSkills, args: &SkillArgs) -> Result<BTreeMap<i32, i32>, String> {
+    let mut skills: BTreeMap<i32, i32> = BTreeMap::new();
+
+    if let Some(skills_filename) = &args.skills_filename {
+        let skills_file = std::fs::read_to_string(skills_filename)
+            .map_err(|e| format!("Cannot read {}: {}", skills_filename.display(), e))?;
+        let skills_file: BTreeMap<String, i32> = serde_json::from_str(&skills_file)
+            .map_err(|e| format!("Corrupt {}: {}", skills_filename.display(), e))?;
+
+        for (skill_id, level) in skills_file {
+            let skill_id = skill_id
+                .parse::<i32>()
+                .map_err(|_| format!("Invalid skill ID '{}'", skill_id))?;
+            skills.insert(skill_id, level);
+        }
+    }
+
+    if let Some(skill_profile) = args.skill_profile {
+        skills = skills::skill_profile(info, skill_profile, &skills)?;
+    }
+
+    Ok(skills)
+}
@@ -0,0 +1,79 @@
+use esf_dogma_engine::calculate::item::{Item, Object};
+use esf_dogma_engine::calculate::Ship;
+use esf_dogma_engine::info::InfoName;
+
+use fit::CliFit;
+
+pub mod attributes;
+pub mod batch;
+pub mod calc;
+pub mod convert;
+pub mod data;
+pub mod diff;
+pub mod explain;
+pub mod fit;
+pub mod output;
+pub mod validate;
+
+/* Tell what was skipped while reading a fit; this goes to stderr, so it doesn't mix with the output. */
+pub fn report_skipped(fit: &CliFit) {
+    for skipped in &fit.skipped {
+        eprintln!("{}: {}", fit.name, skipped);
+    }
+}
+
+/* The item of the calculated ship an object (like the source of an effect) refers to. */
+pub fn object_item<'a>(ship: &'a Ship, object: &Object) -> Option<&'a Item> {
+    match object {
+        Object::Ship => Some(&ship.hull),
+        Object::Item(index) => ship.items.get(*index),
+        Object::Charge(index) => ship.items.get(*index)?.charge.as_deref(),
+        Object::Skill(index) => ship.skills.get(*index),
+        Object::Char => Some(&ship.char),
+        Object::Structure => Some(&ship.structure),
+        Object::Target => Some(&ship.target),
+    }
+}
+
+/* Human-readable name of an object, like "Ship (Rifter)" or "Damage Control II". */
+pub fn object_name(info: &impl InfoName, ship: &Ship, object: &Object) -> String {
+    let type_name = |item: Option<&Item>| {
+        item.and_then(|item| info.type_id_to_name(item.type_id))
+            .unwrap_or_else(|| "unknown".to_string())
+    };
+
+    match object {
+        Object::Ship => format!("Ship ({})", type_name(Some(&ship.hull))),
+        Object::Char => "Character".to_string(),
+        Object::Structure => "Structure".to_string(),
+        Object::Target => "Target".to_string(),
+        Object::Item(_) | Object::Charge(_) | Object::Skill(_) => {
+            type_name(object_item(ship, object))
+        }
+    }
+}
+
+/* The hull, the character, and every item (followed by its charge) of the calculated ship. */
+pub fn ship_objects(ship: &Ship) -> Vec<Object> {
+    let mut objects = vec![Object::Ship, Object::Char];
+    for (index, item) in ship.items.iter().enumerate() {
+        objects.push(Object::Item(index));
+        if item.charge.is_some() {
+            objects.push(Object::Charge(index));
+        }
+    }
+    objects
+}
+
+/* Whether the object goes by the given name; "ship" and "char" are the hull and the
+ * character, anything else is compared (case-insensitive) with the type name. */
+pub fn object_matches(info: &impl InfoName, ship: &Ship, object: &Object, name: &str) -> bool {
+    match (object, name.to_lowercase().as_str()) {
+        (Object::Ship, "ship" | "hull") => true,
+        (Object::Char, "char" | "character") => true,
+        (Object::Item(_) | Object::Charge(_), _) => object_item(ship, object)
+            .and_then(|item| info.type_id_to_name(item.type_id))
+            .is_some_and(|type_name| type_name.eq_ignore_ascii_case(name)),
+        _ => false,
+    }
+}
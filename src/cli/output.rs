@@ -0,0 +1,712 @@
+use std::collections::BTreeMap;
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+use esf_dogma_engine::calculate::item::{self, Item};
+use esf_dogma_engine::calculate::Ship;
+use esf_dogma_engine::info::Info;
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Json,
+    Table,
+}
+
+#[derive(Debug, Serialize)]
+struct OutputCapacitor {
+    stable: bool,
+    depletes_in: f64,
+    capacity: f64,
+    recharge: f64,
+    peak: f64,
+    percentage: f64,
+}
+
+#[derive(Debug, Serialize)]
+struct OutputOffense {
+    dps: f64,
+    dps_with_reload: f64,
+    alpha: f64,
+    drone_dps: f64,
+}
+
+#[derive(Debug, Serialize)]
+struct OutputDefenseRecharge {
+    passive: f64,
+    shield: f64,
+    armor: f64,
+    hull: f64,
+}
+
+#[derive(Debug, Serialize)]
+struct OutputDefenseResist {
+    em: f64,
+    therm: f64,
+    kin: f64,
+    expl: f64,
+}
+
+#[derive(Debug, Serialize)]
+struct OutputDefenseShield {
+    resist: OutputDefenseResist,
+    hp: f64,
+    recharge: f64,
+}
+
+#[derive(Debug, Serialize)]
+struct OutputDefenseArmor {
+    resist: OutputDefenseResist,
+    hp: f64,
+}
+
+#[derive(Debug, Serialize)]
+struct OutputDefenseStructure {
+    resist: OutputDefenseResist,
+    hp: f64,
+}
+
+#[derive(Debug, Serialize)]
+struct OutputDefense {
+    recharge: OutputDefenseRecharge,
+    shield: OutputDefenseShield,
+    armor: OutputDefenseArmor,
+    structure: OutputDefenseStructure,
+    ehp: f64,
+}
+
+#[derive(Debug, Serialize)]
+struct OutputTargeting {
+    lock_range: f64,
+    sensor_strength: f64,
+    scan_resolution: f64,
+    signature_radius: f64,
+    max_locked_targets: f64,
+}
+
+#[derive(Debug, Serialize)]
+struct OutputNavigation {
+    speed: f64,
+    mass: f64,
+    agility: f64,
+    warp_speed: f64,
+    align_time: f64,
+}
+
+#[derive(Debug, Serialize)]
+struct OutputDrones {
+    dps: f64,
+    bandwidth_load: f64,
+    bandwidth: f64,
+    range: f64,
+    active: f64,
+    capacity_load: f64,
+    capacity: f64,
+}
+
+#[derive(Debug, Serialize)]
+struct OutputCpu {
+    free: f64,
+    capacity: f64,
+}
+
+#[derive(Debug, Serialize)]
+struct OutputPower {
+    free: f64,
+    capacity: f64,
+}
+
+#[derive(Debug, Serialize)]
+struct OutputSlots {
+    hi_1: String,
+    hi_2: String,
+    hi_3: String,
+    hi_4: String,
+    hi_5: String,
+    hi_6: String,
+    hi_7: String,
+    hi_8: String,
+    med_1: String,
+    med_2: String,
+    med_3: String,
+    med_4: String,
+    med_5: String,
+    med_6: String,
+    med_7: String,
+    med_8: String,
+    lo_1: String,
+    lo_2: String,
+    lo_3: String,
+    lo_4: String,
+    lo_5: String,
+    lo_6: String,
+    lo_7: String,
+    lo_8: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Output {
+    capacitor: OutputCapacitor,
+    offense: OutputOffense,
+    defense: OutputDefense,
+    targeting: OutputTargeting,
+    navigation: OutputNavigation,
+    drones: OutputDrones,
+    cpu: OutputCpu,
+    power: OutputPower,
+    slots: OutputSlots,
+}
+
+fn get_attribute_by_name(
+    info: &impl Info,
+    attributes: &BTreeMap<i32, item::Attribute>,
+    name: &str,
+) -> f64 {
+    let attribute_id = info.attribute_name_to_id(name);
+    let default_attribute = info.get_dogma_attribute(attribute_id);
+
+    let attribute = attributes
+        .iter()
+        .find(|attribute| *attribute.0 == attribute_id);
+    if let Some(attribute) = attribute {
+        attribute.1.value.unwrap_or(default_attribute.defaultValue)
+    } else {
+        default_attribute.defaultValue
+    }
+}
+
+fn effect_category_to_name(items: &[Item], slot_type: item::SlotType, index: i32) -> String {
+    let item = items
+        .iter()
+        .find(|item| item.slot.index == Some(index) && item.slot.r#type == slot_type);
+
+    if let Some(item) = item {
+        match item.state {
+            item::EffectCategory::Passive => "passive",
+            item::EffectCategory::Online => "online",
+            item::EffectCategory::Active => "active",
+            item::EffectCategory::Overload => "overload",
+            _ => "unknown",
+        }
+    } else {
+        "empty"
+    }
+    .to_string()
+}
+
+/* Some of EVEShipFit's own attributes (with negative IDs) the statistics are based on.
+ * These are only in EVEShipFit's data files; CCP's SDE has none of them. */
+const EVESHIPFIT_ATTRIBUTES: [&str; 3] =
+    ["capacitorPeakDelta", "damagePerSecondWithoutReload", "ehp"];
+
+/* Check the loaded data can calculate the statistics, before calculating any fit. */
+pub fn check_statistics_attributes(info: &impl Info) -> Result<(), String> {
+    match EVESHIPFIT_ATTRIBUTES
+        .iter()
+        .all(|name| info.attribute_name_to_id(name) == 0)
+    {
+        true => Err(format!(
+            "The loaded data has none of EVEShipFit's attributes ({}); the statistics need EVEShipFit's data files, not CCP's SDE",
+            EVESHIPFIT_ATTRIBUTES.join(", ")
+        )),
+        false => Ok(()),
+    }
+}
+
+/* The fixed set of statistics of a fit, as shown by EVEShip.fit. */
+pub fn output(info: &impl Info, statistics: &Ship) -> Output {
+    Output {
+        capacitor: OutputCapacitor {
+            stable: get_attribute_by_name(info, &statistics.hull.attributes, "capacitorDepletesIn")
+                == -1.0,
+            depletes_in: get_attribute_by_name(
+                info,
+                &statistics.hull.attributes,
+                "capacitorDepletesIn",
+            ),
+            capacity: get_attribute_by_name(info, &statistics.hull.attributes, "capacitorCapacity")
+                .floor(),
+            recharge: get_attribute_by_name(info, &statistics.hull.attributes, "rechargeRate")
+                / 1000.0,
+            peak: get_attribute_by_name(info, &statistics.hull.attributes, "capacitorPeakDelta"),
+            percentage: get_attribute_by_name(
+                info,
+                &statistics.hull.attributes,
+                "capacitorPeakDeltaPercentage",
+            ),
+        },
+        offense: OutputOffense {
+            dps: get_attribute_by_name(
+                info,
+                &statistics.hull.attributes,
+                "damagePerSecondWithoutReload",
+            ),
+            dps_with_reload: get_attribute_by_name(
+                info,
+                &statistics.hull.attributes,
+                "damagePerSecondWithReload",
+            ),
+            alpha: get_attribute_by_name(info, &statistics.hull.attributes, "damageAlpha"),
+            drone_dps: get_attribute_by_name(
+                info,
+                &statistics.hull.attributes,
+                "droneDamagePerSecond",
+            ),
+        },
+        defense: OutputDefense {
+            recharge: OutputDefenseRecharge {
+                passive: get_attribute_by_name(
+                    info,
+                    &statistics.hull.attributes,
+                    "passiveShieldRechargeRate",
+                ),
+                shield: get_attribute_by_name(info, &statistics.hull.attributes, "shieldBoostRate"),
+                armor: get_attribute_by_name(info, &statistics.hull.attributes, "armorRepairRate"),
+                hull: get_attribute_by_name(info, &statistics.hull.attributes, "hullRepairRate"),
+            },
+            shield: OutputDefenseShield {
+                resist: OutputDefenseResist {
+                    em: (1.0
+                        - get_attribute_by_name(
+                            info,
+                            &statistics.hull.attributes,
+                            "shieldEmDamageResonance",
+                        ))
+                        * 100.0,
+                    therm: (1.0
+                        - get_attribute_by_name(
+                            info,
+                            &statistics.hull.attributes,
+                            "shieldThermalDamageResonance",
+                        ))
+                        * 100.0,
+                    kin: (1.0
+                        - get_attribute_by_name(
+                            info,
+                            &statistics.hull.attributes,
+                            "shieldKineticDamageResonance",
+                        ))
+                        * 100.0,
+                    expl: (1.0
+                        - get_attribute_by_name(
+                            info,
+                            &statistics.hull.attributes,
+                            "shieldExplosiveDamageResonance",
+                        ))
+                        * 100.0,
+                },
+                hp: get_attribute_by_name(info, &statistics.hull.attributes, "shieldCapacity"),
+                recharge: get_attribute_by_name(
+                    info,
+                    &statistics.hull.attributes,
+                    "shieldRechargeRate",
+                ) / 1000.0,
+            },
+            armor: OutputDefenseArmor {
+                resist: OutputDefenseResist {
+                    em: (1.0
+                        - get_attribute_by_name(
+                            info,
+                            &statistics.hull.attributes,
+                            "armorEmDamageResonance",
+                        ))
+                        * 100.0,
+                    therm: (1.0
+                        - get_attribute_by_name(
+                            info,
+                            &statistics.hull.attributes,
+                            "armorThermalDamageResonance",
+                        ))
+                        * 100.0,
+                    kin: (1.0
+                        - get_attribute_by_name(
+                            info,
+                            &statistics.hull.attributes,
+                            "armorKineticDamageResonance",
+                        ))
+                        * 100.0,
+                    expl: (1.0
+                        - get_attribute_by_name(
+                            info,
+                            &statistics.hull.attributes,
+                            "armorExplosiveDamageResonance",
+                        ))
+                        * 100.0,
+                },
+                hp: get_attribute_by_name(info, &statistics.hull.attributes, "armorHP"),
+            },
+            structure: OutputDefenseStructure {
+                resist: OutputDefenseResist {
+                    em: (1.0
+                        - get_attribute_by_name(
+                            info,
+                            &statistics.hull.attributes,
+                            "emDamageResonance",
+                        ))
+                        * 100.0,
+                    therm: (1.0
+                        - get_attribute_by_name(
+                            info,
+                            &statistics.hull.attributes,
+                            "thermalDamageResonance",
+                        ))
+                        * 100.0,
+                    kin: (1.0
+                        - get_attribute_by_name(
+                            info,
+                            &statistics.hull.attributes,
+                            "kineticDamageResonance",
+                        ))
+                        * 100.0,
+                    expl: (1.0
+                        - get_attribute_by_name(
+                            info,
+                            &statistics.hull.attributes,
+                            "explosiveDamageResonance",
+                        ))
+                        * 100.0,
+                },
+                hp: get_attribute_by_name(info, &statistics.hull.attributes, "hp"),
+            },
+            ehp: get_attribute_by_name(info, &statistics.hull.attributes, "ehp"),
+        },
+        targeting: OutputTargeting {
+            lock_range: get_attribute_by_name(info, &statistics.hull.attributes, "maxTargetRange")
+                / 1000.0,
+            sensor_strength: get_attribute_by_name(
+                info,
+                &statistics.hull.attributes,
+                "scanStrength",
+            ),
+            scan_resolution: get_attribute_by_name(
+                info,
+                &statistics.hull.attributes,
+                "scanResolution",
+            ),
+            signature_radius: get_attribute_by_name(
+                info,
+                &statistics.hull.attributes,
+                "signatureRadius",
+            ),
+            max_locked_targets: get_attribute_by_name(
+                info,
+                &statistics.hull.attributes,
+                "maxLockedTargets",
+            ),
+        },
+        navigation: OutputNavigation {
+            speed: get_attribute_by_name(info, &statistics.hull.attributes, "maxVelocity"),
+            mass: get_attribute_by_name(info, &statistics.hull.attributes, "mass") / 1000.0,
+            agility: get_attribute_by_name(info, &statistics.hull.attributes, "agility"),
+            warp_speed: get_attribute_by_name(
+                info,
+                &statistics.hull.attributes,
+                "warpSpeedMultiplier",
+            ),
+            align_time: get_attribute_by_name(info, &statistics.hull.attributes, "alignTime"),
+        },
+        drones: OutputDrones {
+            dps: get_attribute_by_name(info, &statistics.hull.attributes, "droneDamagePerSecond"),
+            bandwidth_load: get_attribute_by_name(
+                info,
+                &statistics.hull.attributes,
+                "droneBandwidthLoad",
+            ),
+            bandwidth: get_attribute_by_name(info, &statistics.hull.attributes, "droneBandwidth"),
+            capacity_load: get_attribute_by_name(
+                info,
+                &statistics.hull.attributes,
+                "droneCapacityLoad",
+            ),
+            capacity: get_attribute_by_name(info, &statistics.hull.attributes, "droneCapacity"),
+            range: get_attribute_by_name(info, &statistics.char.attributes, "droneControlDistance")
+                / 1000.0,
+            active: get_attribute_by_name(info, &statistics.hull.attributes, "droneActive"),
+        },
+        cpu: OutputCpu {
+            free: get_attribute_by_name(info, &statistics.hull.attributes, "cpuFree"),
+            capacity: get_attribute_by_name(info, &statistics.hull.attributes, "cpuOutput"),
+        },
+        power: OutputPower {
+            free: get_attribute_by_name(info, &statistics.hull.attributes, "powerFree"),
+            capacity: get_attribute_by_name(info, &statistics.hull.attributes, "powerOutput"),
+        },
+        slots: OutputSlots {
+            hi_1: effect_category_to_name(&statistics.items, item::SlotType::High, 0),
+            hi_2: effect_category_to_name(&statistics.items, item::SlotType::High, 1),
+            hi_3: effect_category_to_name(&statistics.items, item::SlotType::High, 2),
+            hi_4: effect_category_to_name(&statistics.items, item::SlotType::High, 3),
+            hi_5: effect_category_to_name(&statistics.items, item::SlotType::High, 4),
+            hi_6: effect_category_to_name(&statistics.items, item::SlotType::High, 5),
+            hi_7: effect_category_to_name(&statistics.items, item::SlotType::High, 6),
+            hi_8: effect_category_to_name(&statistics.items, item::SlotType::High, 7),
+            med_1: effect_category_to_name(&statistics.items, item::SlotType::Medium, 0),
+            med_2: effect_category_to_name(&statistics.items, item::SlotType::Medium, 1),
+            med_3: effect_category_to_name(&statistics.items, item::SlotType::Medium, 2),
+            med_4: effect_category_to_name(&statistics.items, item::SlotType::Medium, 3),
+            med_5: effect_category_to_name(&statistics.items, item::SlotType::Medium, 4),
+            med_6: effect_category_to_name(&statistics.items, item::SlotType::Medium, 5),
+            med_7: effect_category_to_name(&statistics.items, item::SlotType::Medium, 6),
+            med_8: effect_category_to_name(&statistics.items, item::SlotType::Medium, 7),
+            lo_1: effect_category_to_name(&statistics.items, item::SlotType::Low, 0),
+            lo_2: effect_category_to_name(&statistics.items, item::SlotType::Low, 1),
+            lo_3: effect_category_to_name(&statistics.items, item::SlotType::Low, 2),
+            lo_4: effect_category_to_name(&statistics.items, item::SlotType::Low, 3),
+            lo_5: effect_category_to_name(&statistics.items, item::SlotType::Low, 4),
+            lo_6: effect_category_to_name(&statistics.items, item::SlotType::Low, 5),
+            lo_7: effect_category_to_name(&statistics.items, item::SlotType::Low, 6),
+            lo_8: effect_category_to_name(&statistics.items, item::SlotType::Low, 7),
+        },
+    }
+}
+
+/* Format a number with thousands separators and a fixed number of decimals. */
+fn number(value: f64, decimals: usize) -> String {
+    let formatted = format!("{:.*}", decimals, value.abs());
+    let (integer, fraction) = match formatted.split_once('.') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (formatted.as_str(), None),
+    };
+
+    let mut result = String::new();
+    for (index, digit) in integer.chars().enumerate() {
+        if index > 0 && (integer.len() - index) % 3 == 0 {
+            result.push(',');
+        }
+        result.push(digit);
+    }
+    if let Some(fraction) = fraction {
+        result = format!("{}.{}", result, fraction);
+    }
+
+    /* Don't show "-0.0" for values that round to zero. */
+    match value < 0.0 && result.chars().any(|c| c.is_ascii_digit() && c != '0') {
+        true => format!("-{}", result),
+        false => result,
+    }
+}
+
+fn duration(seconds: f64) -> String {
+    let seconds = seconds.round() as i64;
+    match (seconds / 3600, seconds / 60 % 60, seconds % 60) {
+        (0, 0, s) => format!("{}s", s),
+        (0, m, s) => format!("{}m {}s", m, s),
+        (h, m, s) => format!("{}h {}m {}s", h, m, s),
+    }
+}
+
+fn row(label: &str, value: String) -> String {
+    format!("  {:<18}{}", label, value)
+}
+
+fn resist_row(label: &str, hp: f64, resist: &OutputDefenseResist) -> String {
+    format!(
+        "  {:<10}{:>10}{:>8}%{:>8}%{:>8}%{:>8}%",
+        label,
+        number(hp, 0),
+        number(resist.em, 1),
+        number(resist.therm, 1),
+        number(resist.kin, 1),
+        number(resist.expl, 1)
+    )
+}
+
+fn slots_row(label: &str, slots: [&String; 8]) -> String {
+    let slots = slots
+        .into_iter()
+        .filter(|slot| *slot != "empty")
+        .map(|slot| slot.as_str())
+        .collect::<Vec<_>>();
+
+    match slots.is_empty() {
+        true => row(label, "-".to_string()),
+        false => row(label, slots.join(", ")),
+    }
+}
+
+impl Output {
+    /* Render the statistics as a human-readable report, for use in a terminal. */
+    pub fn to_table(&self) -> String {
+        let capacitor = &self.capacitor;
+        let defense = &self.defense;
+        let slots = &self.slots;
+
+        let stability = match capacitor.stable {
+            true => format!("Stable at {}%", number(capacitor.percentage, 1)),
+            false => format!("Lasts {}", duration(capacitor.depletes_in)),
+        };
+
+        [
+            "Capacitor".to_string(),
+            row("Capacity", format!("{} GJ", number(capacitor.capacity, 0))),
+            row(
+                "Recharge time",
+                format!("{} s", number(capacitor.recharge, 1)),
+            ),
+            row("Stability", stability),
+            row("Peak delta", format!("{} GJ/s", number(capacitor.peak, 2))),
+            "Offense".to_string(),
+            row(
+                "DPS",
+                format!(
+                    "{} ({} with reload)",
+                    number(self.offense.dps, 1),
+                    number(self.offense.dps_with_reload, 1)
+                ),
+            ),
+            row("Alpha", format!("{} HP", number(self.offense.alpha, 0))),
+            row("Drone DPS", number(self.offense.drone_dps, 1)),
+            "Defense".to_string(),
+            row("EHP", format!("{} HP", number(defense.ehp, 0))),
+            format!(
+                "  {:<10}{:>10}{:>9}{:>9}{:>9}{:>9}",
+                "", "HP", "EM", "Therm", "Kin", "Expl"
+            ),
+            resist_row("Shield", defense.shield.hp, &defense.shield.resist),
+            resist_row("Armor", defense.armor.hp, &defense.armor.resist),
+            resist_row("Structure", defense.structure.hp, &defense.structure.resist),
+            row(
+                "Shield recharge",
+                format!("{} s", number(defense.shield.recharge, 1)),
+            ),
+            row(
+                "Passive recharge",
+                format!("{} HP/s", number(defense.recharge.passive, 1)),
+            ),
+            row(
+                "Shield boost",
+                format!("{} HP/s", number(defense.recharge.shield, 1)),
+            ),
+            row(
+                "Armor repair",
+                format!("{} HP/s", number(defense.recharge.armor, 1)),
+            ),
+            row(
+                "Hull repair",
+                format!("{} HP/s", number(defense.recharge.hull, 1)),
+            ),
+            "Targeting".to_string(),
+            row(
+                "Lock range",
+                format!("{} km", number(self.targeting.lock_range, 2)),
+            ),
+            row(
+                "Sensor strength",
+                format!("{} points", number(self.targeting.sensor_strength, 1)),
+            ),
+            row(
+                "Scan resolution",
+                format!("{} mm", number(self.targeting.scan_resolution, 0)),
+            ),
+            row(
+                "Signature radius",
+                format!("{} m", number(self.targeting.signature_radius, 0)),
+            ),
+            row("Max targets", number(self.targeting.max_locked_targets, 0)),
+            "Navigation".to_string(),
+            row("Speed", format!("{} m/s", number(self.navigation.speed, 1))),
+            row("Mass", format!("{} t", number(self.navigation.mass, 0))),
+            row(
+                "Inertia modifier",
+                format!("{}x", number(self.navigation.agility, 3)),
+            ),
+            row(
+                "Warp speed",
+                format!("{} AU/s", number(self.navigation.warp_speed, 2)),
+            ),
+            row(
+                "Align time",
+                format!("{} s", number(self.navigation.align_time, 2)),
+            ),
+            "Drones".to_string(),
+            row("DPS", number(self.drones.dps, 1)),
+            row(
+                "Bandwidth",
+                format!(
+                    "{} / {} Mbit/s",
+                    number(self.drones.bandwidth_load, 0),
+                    number(self.drones.bandwidth, 0)
+                ),
+            ),
+            row(
+                "Bay",
+                format!(
+                    "{} / {} m3",
+                    number(self.drones.capacity_load, 0),
+                    number(self.drones.capacity, 0)
+                ),
+            ),
+            row(
+                "Control range",
+                format!("{} km", number(self.drones.range, 1)),
+            ),
+            row("Max active", number(self.drones.active, 0)),
+            "Fitting".to_string(),
+            row(
+                "CPU",
+                format!(
+                    "{} / {} tf",
+                    number(self.cpu.capacity - self.cpu.free, 1),
+                    number(self.cpu.capacity, 1)
+                ),
+            ),
+            row(
+                "Powergrid",
+                format!(
+                    "{} / {} MW",
+                    number(self.power.capacity - self.power.free, 1),
+                    number(self.power.capacity, 1)
+                ),
+            ),
+            slots_row(
+                "High slots",
+                [
+                    &slots.hi_1,
+                    &slots.hi_2,
+                    &slots.hi_3,
+                    &slots.hi_4,
+                    &slots.hi_5,
+                    &slots.hi_6,
+                    &slots.hi_7,
+                    &slots.hi_8,
+                ],
+            ),
+            slots_row(
+                "Medium slots",
+                [
+                    &slots.med_1,
+                    &slots.med_2,
+                    &slots.med_3,
+                    &slots.med_4,
+                    &slots.med_5,
+                    &slots.med_6,
+                    &slots.med_7,
+                    &slots.med_8,
+                ],
+            ),
+            slots_row(
+                "Low slots",
+                [
+                    &slots.lo_1,
+                    &slots.lo_2,
+                    &slots.lo_3,
+                    &slots.lo_4,
+                    &slots.lo_5,
+                    &slots.lo_6,
+                    &slots.lo_7,
+                    &slots.lo_8,
+                ],
+            ),
+        ]
+        .join("\n")
+    }
+}
+
+/* Print the statistics of a fit in the requested format; JSON is a single line,
+ * the table starts with the name of the fit. */
+pub fn print_output(format: OutputFormat, name: &str, output: &Output) {
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string(output).unwrap()),
+        OutputFormat::Table => println!("{}\n{}\n", name, output.to_table()),
+    }
+}
@@ -0,0 +1,171 @@
+use esf_dogma_engine::calculate::{self, Ship};
+use esf_dogma_engine::data_types::{EsfFit, EsfSlotType};
+use esf_dogma_engine::info::{InfoName, InfoSkills};
+use esf_dogma_engine::rust::{self, fit_type_ids};
+
+use super::fit::{load_skills, read_fits, FitArgs, SkillArgs};
+use super::object_name;
+
+#[derive(clap::Args)]
+pub struct ValidateArgs {
+    #[clap(flatten)]
+    pub fit: FitArgs,
+
+    #[clap(flatten)]
+    pub skills: SkillArgs,
+
+    /// Check whether the loaded data files are consistent with each other, instead of checking fits.
+    #[clap(long)]
+    pub data: bool,
+}
+
+/* Stop listing data problems after this many; one is usually enough to know the files don't match. */
+const MAX_REPORTED_PROBLEMS: usize = 10;
+
+const SLOTS: [(EsfSlotType, &str, &str); 6] = [
+    (EsfSlotType::High, "hiSlots", "high"),
+    (EsfSlotType::Medium, "medSlots", "medium"),
+    (EsfSlotType::Low, "lowSlots", "low"),
+    (EsfSlotType::Rig, "rigSlots", "rig"),
+    (EsfSlotType::SubSystem, "maxSubSystems", "subsystem"),
+    (EsfSlotType::Service, "serviceSlots", "service"),
+];
+
+/* (used, available, name) of the resources a fit can run out of. */
+const RESOURCES: [(&str, &str, &str); 5] = [
+    ("cpuLoad", "cpuOutput", "CPU"),
+    ("powerLoad", "powerOutput", "powergrid"),
+    ("upgradeLoad", "upgradeCapacity", "calibration"),
+    ("droneBandwidthLoad", "droneBandwidth", "drone bandwidth"),
+    ("droneCapacityLoad", "droneCapacity", "drone bay"),
+];
+
+/* Final value of a hull attribute; None if the data doesn't know the attribute. */
+fn hull_value(info: &impl InfoName, ship: &Ship, name: &str) -> Option<f64> {
+    let attribute_id = info.attribute_name_to_id(name);
+    if attribute_id == 0 {
+        return None;
+    }
+
+    let default_value = info.get_dogma_attribute(attribute_id).defaultValue;
+    Some(
+        ship.hull
+            .attributes
+            .get(&attribute_id)
+            .map(|attribute| attribute.value.unwrap_or(attribute.base_value))
+            .unwrap_or(default_value),
+    )
+}
+
+/* Everything that keeps the fit from being used in-game as given. */
+fn fit_problems(info: &impl InfoName, esf_fit: &EsfFit, ship: &Ship) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    for type_id in fit_type_ids(esf_fit) {
+        if info.type_id_to_name(type_id).is_none() {
+            problems.push(format!("Unknown type ID {}", type_id));
+        }
+    }
+
+    for (slot_type, attribute, name) in SLOTS {
+        let used = esf_fit
+            .modules
+            .iter()
+            .filter(|module| module.slot.r#type == slot_type)
+            .count();
+        let available = hull_value(info, ship, attribute).unwrap_or(0.0) as usize;
+        if used > available {
+            problems.push(format!(
+                "{} {} slot modules fitted, but the ship has {} {} slots",
+                used, name, available, name
+            ));
+        }
+    }
+
+    for (used, available, name) in RESOURCES {
+        let (Some(used), Some(available)) = (
+            hull_value(info, ship, used),
+            hull_value(info, ship, available),
+        ) else {
+            continue;
+        };
+        if used > available + 1e-6 {
+            problems.push(format!(
+                "Not enough {}: {} used of {}",
+                name, used, available
+            ));
+        }
+    }
+
+    for stopped_effect in &ship.stopped_effects {
+        problems.push(format!(
+            "{} has its {:?} effect {} stopped by {}",
+            object_name(info, ship, &stopped_effect.item),
+            stopped_effect.category,
+            stopped_effect.effect_id,
+            object_name(info, ship, &stopped_effect.source)
+        ));
+    }
+
+    problems
+}
+
+/* Check every fit of the input, and list the problems per fit. */
+pub fn run(info: &(impl InfoName + InfoSkills), args: ValidateArgs) -> Result<(), String> {
+    let skills = load_skills(info, &args.skills)?;
+    let fits = read_fits(info, &args.fit)?;
+    let fit_count = fits.len();
+
+    let mut invalid = 0;
+    for (index, fit) in fits.into_iter().enumerate() {
+        let (name, problems) = match fit {
+            Err(error) => (format!("Fit {}", index + 1), vec![error]),
+            Ok(fit) => {
+                let input = calculate::Input::new(fit.esf_fit, skills.clone());
+                let ship = calculate::calculate(info, &input);
+
+                /* Skipped lines mean the fit is not what was given. */
+                let mut problems = fit.skipped;
+                problems.extend(fit_problems(info, &input.fit, &ship));
+                (fit.name, problems)
+            }
+        };
+
+        if problems.is_empty() {
+            println!("{}: valid", name);
+            continue;
+        }
+
+        invalid += 1;
+        println!("{}:", name);
+        for problem in problems {
+            for line in problem.lines() {
+                println!("  {}", line);
+            }
+        }
+    }
+
+    match invalid {
+        0 => Ok(()),
+        _ => Err(format!("{} of {} fits are not valid", invalid, fit_count)),
+    }
+}
+
+/* Check that the loaded data files reference each other correctly. */
+pub fn run_data(data: &rust::Data) -> Result<(), String> {
+    let problems = data.validate();
+    if problems.is_empty() {
+        println!("Data files: valid");
+        return Ok(());
+    }
+
+    println!("Data files:");
+    for problem in problems.iter().take(MAX_REPORTED_PROBLEMS) {
+        println!("  {}", problem);
+    }
+    if problems.len() > MAX_REPORTED_PROBLEMS {
+        println!("  (and {} more)", problems.len() - MAX_REPORTED_PROBLEMS);
+    }
+
+    Err(format!("{} problems in the data files", problems.len()))
+}
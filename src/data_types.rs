@@ -6,6 +6,12 @@ use serde_repr::*;
 pub struct Type {
     pub groupID: i32,
     pub categoryID: i32,
+    #[serde(default)]
+    pub published: bool,
+    pub marketGroupID: Option<i32>,
+    pub metaGroupID: Option<i32>,
+    /* The type this is a variation of (Tech II, faction, ..); None for the base type. */
+    pub variationParentTypeID: Option<i32>,
     pub capacity: Option<f64>,
     pub mass: Option<f64>,
     pub radius: Option<f64>,
@@ -69,6 +75,7 @@ pub struct DogmaEffectModifierInfo {
     pub operation: Option<i32>,
     pub groupID: Option<i32>,
     pub skillTypeID: Option<i32>,
+    pub effectID: Option<i32>,
 }
 
 #[allow(non_snake_case, dead_code)]
@@ -91,7 +98,7 @@ pub struct DogmaEffect {
     pub modifierInfo: Vec<DogmaEffectModifierInfo>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum EsfState {
     Passive,
     Online,
@@ -99,7 +106,7 @@ pub enum EsfState {
     Overload,
 }
 
-#[derive(Deserialize, Debug, Eq, Hash, PartialEq)]
+#[derive(Deserialize, Debug, Clone, Copy, Eq, Hash, PartialEq)]
 pub enum EsfSlotType {
     High,
     Medium,
@@ -134,11 +141,39 @@ pub struct EsfDrone {
     pub state: EsfState,
 }
 
+#[derive(Deserialize, Debug)]
+pub struct EsfImplant {
+    pub type_id: i32,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct EsfBooster {
+    pub type_id: i32,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct EsfMode {
+    pub type_id: i32,
+}
+
+/* Cargo is not used for calculation, but most fit formats carry it along. */
+#[derive(Deserialize, Debug, Clone)]
+pub struct EsfCargo {
+    pub type_id: i32,
+    pub quantity: i32,
+}
+
 #[derive(Deserialize, Debug)]
 pub struct EsfFit {
     pub ship_type_id: i32,
     pub modules: Vec<EsfModule>,
     pub drones: Vec<EsfDrone>,
+    #[serde(default)]
+    pub implants: Vec<EsfImplant>,
+    #[serde(default)]
+    pub boosters: Vec<EsfBooster>,
+    #[serde(default)]
+    pub mode: Option<EsfMode>,
 }
 
 impl From<i32> for DogmaEffectModifierInfoDomain {
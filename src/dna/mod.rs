@@ -0,0 +1,244 @@
+use std::collections::HashMap;
+
+use crate::data_types;
+use crate::fit_common::{
+    find_slot_type_index, CATEGORY_CHARGE_ID, CATEGORY_DRONE_ID, CATEGORY_IMPLANT_ID,
+    GROUP_BOOSTER_ID, GROUP_SHIP_MODIFIER_ID,
+};
+use crate::info::Info;
+
+const ATTRIBUTE_CHARGE_SIZE_ID: i32 = 128;
+const ATTRIBUTE_CHARGE_GROUP_IDS: [i32; 5] = [604, 605, 606, 609, 610];
+
+/* Order in which the client writes the modules of a fit in DNA. */
+const MODULE_SLOTS: [data_types::EsfSlotType; 6] = [
+    data_types::EsfSlotType::SubSystem,
+    data_types::EsfSlotType::High,
+    data_types::EsfSlotType::Medium,
+    data_types::EsfSlotType::Low,
+    data_types::EsfSlotType::Rig,
+    data_types::EsfSlotType::Service,
+];
+
+pub struct DnaFit {
+    pub esf_fit: data_types::EsfFit,
+    pub cargo: Vec<data_types::EsfCargo>,
+}
+
+fn get_attribute(info: &impl Info, type_id: i32, attribute_id: i32) -> Option<f64> {
+    info.get_dogma_attributes(type_id)
+        .iter()
+        .find(|attribute| attribute.attributeID == attribute_id)
+        .map(|attribute| attribute.value)
+}
+
+/* Whether a module can load the given charge; same charge group, and same charge size if the module has one. */
+fn can_load_charge(info: &impl Info, module_type_id: i32, charge_type_id: i32) -> bool {
+    let charge_group_id = info.get_type(charge_type_id).groupID as f64;
+
+    let group_matches = ATTRIBUTE_CHARGE_GROUP_IDS.iter().any(|attribute_id| {
+        get_attribute(info, module_type_id, *attribute_id) == Some(charge_group_id)
+    });
+    if !group_matches {
+        return false;
+    }
+
+    match get_attribute(info, module_type_id, ATTRIBUTE_CHARGE_SIZE_ID) {
+        Some(charge_size) => {
+            get_attribute(info, charge_type_id, ATTRIBUTE_CHARGE_SIZE_ID) == Some(charge_size)
+        }
+        None => true,
+    }
+}
+
+fn parse_type_id(type_id: &str) -> Result<i32, String> {
+    type_id
+        .parse::<i32>()
+        .map_err(|_| format!("Invalid type ID '{}'", type_id))
+}
+
+/* Load a ship DNA string ("<ship>:<type>;<quantity>:...::"), optionally as chat link
+ * ("fitting:<ship>:...::"), and return an ESF fit structure.
+ *
+ * DNA only lists type IDs; modules get their slot by their slot effect, drones,
+ * implants, boosters and ship modes are recognised by their category / group.
+ * Charges are loaded one per module, in the modules that can use them; as DNA doesn't
+ * tell which module had which charge, this is a best guess. Charges that are left over
+ * (spare ammo, Nanite Repair Paste, ..) and entries with a "_" suffix are cargo. */
+pub fn load_dna(info: &impl Info, dna: &str) -> Result<DnaFit, String> {
+    let dna = dna.trim();
+    let dna = dna.strip_prefix("fitting:").unwrap_or(dna);
+    let dna = dna.trim_end_matches(':');
+
+    let mut entries = dna.split(':');
+    let ship_type_id = parse_type_id(entries.next().unwrap_or_default())?;
+
+    let mut dna_fit = DnaFit {
+        esf_fit: data_types::EsfFit {
+            ship_type_id,
+            modules: Vec::new(),
+            drones: Vec::new(),
+            implants: Vec::new(),
+            boosters: Vec::new(),
+            mode: None,
+        },
+        cargo: Vec::new(),
+    };
+
+    let mut module_slots: HashMap<data_types::EsfSlotType, i32> = HashMap::new();
+    let mut charges = Vec::new();
+
+    for entry in entries.filter(|entry| !entry.is_empty()) {
+        let Some((type_id, quantity)) = entry.split_once(';') else {
+            return Err(format!("Invalid DNA entry '{}'", entry));
+        };
+        let quantity = match quantity.parse::<i32>() {
+            Ok(quantity) if quantity > 0 => quantity,
+            _ => return Err(format!("Invalid quantity in DNA entry '{}'", entry)),
+        };
+
+        if let Some(type_id) = type_id.strip_suffix('_') {
+            dna_fit.cargo.push(data_types::EsfCargo {
+                type_id: parse_type_id(type_id)?,
+                quantity,
+            });
+            continue;
+        }
+
+        let type_id = parse_type_id(type_id)?;
+        let r#type = info.get_type(type_id);
+
+        match (r#type.categoryID, r#type.groupID) {
+            (CATEGORY_CHARGE_ID, _) => charges.push((type_id, quantity)),
+            (CATEGORY_DRONE_ID, _) => {
+                for _ in 0..quantity {
+                    dna_fit.esf_fit.drones.push(data_types::EsfDrone {
+                        type_id,
+                        state: data_types::EsfState::Active,
+                    });
+                }
+            }
+            (CATEGORY_IMPLANT_ID, GROUP_BOOSTER_ID) => {
+                dna_fit
+                    .esf_fit
+                    .boosters
+                    .push(data_types::EsfBooster { type_id });
+            }
+            (CATEGORY_IMPLANT_ID, _) => {
+                dna_fit
+                    .esf_fit
+                    .implants
+                    .push(data_types::EsfImplant { type_id });
+            }
+            (_, GROUP_SHIP_MODIFIER_ID) => {
+                dna_fit.esf_fit.mode = Some(data_types::EsfMode { type_id });
+            }
+            _ => {
+                for _ in 0..quantity {
+                    let Some((slot_type, index)) =
+                        find_slot_type_index(info, type_id, &mut module_slots)
+                    else {
+                        return Err(format!("Type {} does not fit in any slot", type_id));
+                    };
+
+                    dna_fit.esf_fit.modules.push(data_types::EsfModule {
+                        type_id,
+                        slot: data_types::EsfSlot {
+                            r#type: slot_type,
+                            index,
+                        },
+                        state: data_types::EsfState::Active,
+                        charge: None,
+                    });
+                }
+            }
+        }
+    }
+
+    for (charge_type_id, mut quantity) in charges {
+        for module in &mut dna_fit.esf_fit.modules {
+            if quantity == 0 {
+                break;
+            }
+
+            if module.charge.is_none() && can_load_charge(info, module.type_id, charge_type_id) {
+                module.charge = Some(data_types::EsfCharge {
+                    type_id: charge_type_id,
+                });
+                quantity -= 1;
+            }
+        }
+
+        if quantity > 0 {
+            match dna_fit
+                .cargo
+                .iter_mut()
+                .find(|cargo| cargo.type_id == charge_type_id)
+            {
+                Some(cargo) => cargo.quantity += quantity,
+                None => dna_fit.cargo.push(data_types::EsfCargo {
+                    type_id: charge_type_id,
+                    quantity,
+                }),
+            }
+        }
+    }
+
+    Ok(dna_fit)
+}
+
+/* Add "<type>;<quantity>" entries to the DNA, merging entries of the same type. */
+fn push_entries(dna: &mut String, type_ids: impl Iterator<Item = i32>) {
+    let mut entries: Vec<(i32, i32)> = Vec::new();
+    for type_id in type_ids {
+        match entries.iter_mut().find(|(entry, _)| *entry == type_id) {
+            Some((_, quantity)) => *quantity += 1,
+            None => entries.push((type_id, 1)),
+        }
+    }
+
+    for (type_id, quantity) in entries {
+        dna.push_str(&format!("{};{}:", type_id, quantity));
+    }
+}
+
+/* Save an ESF fit (with cargo) as ship DNA string. Module states are not part of DNA. */
+pub fn save_dna(esf_fit: &data_types::EsfFit, cargo: &[data_types::EsfCargo]) -> String {
+    let mut dna = format!("{}:", esf_fit.ship_type_id);
+
+    for slot_type in &MODULE_SLOTS {
+        let mut modules: Vec<&data_types::EsfModule> = esf_fit
+            .modules
+            .iter()
+            .filter(|module| module.slot.r#type == *slot_type)
+            .collect();
+        modules.sort_by_key(|module| module.slot.index);
+
+        push_entries(&mut dna, modules.iter().map(|module| module.type_id));
+    }
+
+    push_entries(&mut dna, esf_fit.drones.iter().map(|drone| drone.type_id));
+    push_entries(
+        &mut dna,
+        esf_fit.implants.iter().map(|implant| implant.type_id),
+    );
+    push_entries(
+        &mut dna,
+        esf_fit.boosters.iter().map(|booster| booster.type_id),
+    );
+    push_entries(&mut dna, esf_fit.mode.iter().map(|mode| mode.type_id));
+    push_entries(
+        &mut dna,
+        esf_fit
+            .modules
+            .iter()
+            .filter_map(|module| module.charge.as_ref().map(|charge| charge.type_id)),
+    );
+
+    for cargo in cargo {
+        dna.push_str(&format!("{}_;{}:", cargo.type_id, cargo.quantity));
+    }
+
+    dna.push(':');
+    dna
+}
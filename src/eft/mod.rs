@@ -1,12 +1,18 @@
 use std::collections::HashMap;
+use std::fmt;
 
 use crate::data_types;
+use crate::fit_common::{
+    find_slot_type_index, lookup_type, CATEGORY_CHARGE_ID, CATEGORY_DRONE_ID, CATEGORY_IMPLANT_ID,
+    GROUP_BOOSTER_ID, GROUP_SHIP_MODIFIER_ID,
+};
 use crate::info::InfoName;
 
-pub struct EftCargo {
-    pub type_id: i32,
-    pub quantity: i32,
-}
+mod save;
+
+pub use save::{save_eft, save_esf_fit};
+
+pub type EftCargo = data_types::EsfCargo;
 
 pub struct EftFit {
     pub name: String,
@@ -14,238 +20,430 @@ pub struct EftFit {
     pub cargo: Vec<EftCargo>,
 }
 
-fn section_iter(eft_lines: Vec<&str>) -> impl Iterator<Item = Vec<&str>> {
-    let mut section: Vec<&str> = Vec::new();
+/* A problem found while parsing an EFT; line numbers start at 1. */
+#[derive(Debug, Clone)]
+pub struct EftDiagnostic {
+    pub line: usize,
+    pub reason: String,
+}
 
-    let mut eft_lines = eft_lines
-        .into_iter()
-        .skip(1)
-        .fold(Vec::new(), |mut sections, line| {
-            if line.is_empty() {
-                if section.len() > 0 {
-                    sections.push(section.clone());
-                    section.clear();
-                }
-            } else {
-                section.push(line);
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EftMode {
+    /* Any problem fails the whole fit. */
+    Strict,
+    /* Lines with problems are skipped, and reported as diagnostics. */
+    Lenient,
+}
+
+/* The fit with the problems that were skipped, or all problems if the fit couldn't be parsed. */
+pub type EftResult = Result<(EftFit, Vec<EftDiagnostic>), Vec<EftDiagnostic>>;
+
+impl fmt::Display for EftDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Line {}: {}", self.line, self.reason)
+    }
+}
+
+/* Split the lines (after the header) in sections, which are separated by one or more empty lines. */
+fn section_iter<'a>(
+    eft_lines: impl Iterator<Item = (usize, &'a str)>,
+) -> impl Iterator<Item = Vec<(usize, &'a str)>> {
+    let mut sections = Vec::new();
+    let mut section = Vec::new();
+
+    for (line_number, line) in eft_lines {
+        if line.is_empty() {
+            if !section.is_empty() {
+                sections.push(std::mem::take(&mut section));
             }
+        } else {
+            section.push((line_number, line));
+        }
+    }
+
+    if !section.is_empty() {
+        sections.push(section);
+    }
 
-            sections
-        });
+    sections.into_iter()
+}
 
-    if section.len() > 0 {
-        eft_lines.push(section);
+/* Split "<Type Name> x<Quantity>" on the trailing quantity token. As type names
+ * can contain an "x" themselves, only an "x" followed by nothing but digits, and
+ * preceded by whitespace, counts. */
+fn split_quantity(line: &str) -> Option<(&str, &str)> {
+    let (type_name, quantity) = line.rsplit_once('x')?;
+
+    if !type_name.ends_with(char::is_whitespace)
+        || quantity.is_empty()
+        || !quantity.chars().all(|c| c.is_ascii_digit())
+    {
+        return None;
     }
 
-    eft_lines.into_iter()
+    Some((type_name.trim_end(), quantity))
+}
+
+/* Split an optional state marker from the end of a line. Standard EFT only knows "/offline";
+ * as extension "/online", "/active" and "/overload" are accepted too, on any line (including
+ * rigs and drones). For drones, "/offline" means they are in the drone bay. */
+fn split_state(line: &str) -> (&str, Option<data_types::EsfState>) {
+    let Some((rest, marker)) = line.rsplit_once('/') else {
+        return (line, None);
+    };
+
+    let state = match marker.trim() {
+        "offline" => data_types::EsfState::Passive,
+        "online" => data_types::EsfState::Online,
+        "active" => data_types::EsfState::Active,
+        "overload" => data_types::EsfState::Overload,
+        _ => return (line, None),
+    };
+
+    (rest.trim_end(), Some(state))
+}
+
+fn empty_slot_type(line: &str) -> Result<data_types::EsfSlotType, String> {
+    match line {
+        "[Empty High slot]" => Ok(data_types::EsfSlotType::High),
+        "[Empty Med slot]" => Ok(data_types::EsfSlotType::Medium),
+        "[Empty Low slot]" => Ok(data_types::EsfSlotType::Low),
+        "[Empty Rig slot]" => Ok(data_types::EsfSlotType::Rig),
+        "[Empty Subsystem slot]" => Ok(data_types::EsfSlotType::SubSystem),
+        "[Empty Service slot]" => Ok(data_types::EsfSlotType::Service),
+        _ => Err(format!("Unknown empty slot '{}'", line)),
+    }
 }
 
-fn find_slot_type_index(
+/* What a line without quantity turned out to be. */
+enum EftLine {
+    Empty(data_types::EsfSlotType),
+    Module(data_types::EsfModule),
+    Implant(i32),
+    Booster(i32),
+    Mode(i32),
+    Charge(i32, i32),
+}
+
+/* Parse a line without quantity. This is mostly "<Module Name>" or "<Module Name>, <Charge Name>",
+ * optionally followed by a state marker. But implants, boosters, ship modes and loose charges are
+ * listed the same way, so those are recognised by their category / group. */
+fn load_module_line(
     info: &impl InfoName,
-    type_id: i32,
+    line: &str,
     module_slots: &mut HashMap<data_types::EsfSlotType, i32>,
-) -> Option<(data_types::EsfSlotType, i32)> {
-    let effects = info.get_dogma_effects(type_id);
-
-    for effect in &effects {
-        match effect.effectID {
-            11 => {
-                let index = module_slots
-                    .entry(data_types::EsfSlotType::Low)
-                    .or_insert(0);
-                *index += 1;
-                return Some((data_types::EsfSlotType::Low, *index - 1));
-            }
-            12 => {
-                let index = module_slots
-                    .entry(data_types::EsfSlotType::High)
-                    .or_insert(0);
-                *index += 1;
-                return Some((data_types::EsfSlotType::High, *index - 1));
-            }
-            13 => {
-                let index = module_slots
-                    .entry(data_types::EsfSlotType::Medium)
-                    .or_insert(0);
-                *index += 1;
-                return Some((data_types::EsfSlotType::Medium, *index - 1));
-            }
-            2663 => {
-                let index = module_slots
-                    .entry(data_types::EsfSlotType::Rig)
-                    .or_insert(0);
-                *index += 1;
-                return Some((data_types::EsfSlotType::Rig, *index - 1));
-            }
-            3772 => {
-                let index = module_slots
-                    .entry(data_types::EsfSlotType::SubSystem)
-                    .or_insert(0);
-                *index += 1;
-                return Some((data_types::EsfSlotType::SubSystem, *index - 1));
-            }
-            6306 => {
-                let index = module_slots
-                    .entry(data_types::EsfSlotType::Service)
-                    .or_insert(0);
-                *index += 1;
-                return Some((data_types::EsfSlotType::Service, *index - 1));
-            }
+) -> Result<EftLine, String> {
+    if line.starts_with("[Empty") {
+        let slot_type = empty_slot_type(line)?;
+
+        let index = module_slots.entry(slot_type).or_insert(0);
+        *index += 1;
+        return Ok(EftLine::Empty(slot_type));
+    }
+
+    let (line_without_state, state) = split_state(line);
+
+    /* Charge lines may appear in the module sections with or without an "x<quantity>";
+     * a line with a quantity must be a charge. */
+    if split_quantity(line_without_state).is_some() {
+        let (type_id, quantity, _) = load_quantity_line(info, line)?;
+        if info.get_type(type_id).categoryID != CATEGORY_CHARGE_ID {
+            return Err(format!("Unexpected quantity in module section: '{}'", line));
+        }
+        return Ok(EftLine::Charge(type_id, quantity));
+    }
+
+    let line = line_without_state;
+
+    let (module_name, charge_name) = match line.split_once(',') {
+        Some((module_name, charge_name)) => (module_name.trim(), Some(charge_name.trim())),
+        None => (line.trim(), None),
+    };
+
+    let module_type_id = lookup_type(info, module_name)?;
+
+    /* A state marker means nothing for these; it is ignored. */
+    if charge_name.is_none() {
+        let r#type = info.get_type(module_type_id);
+        match (r#type.categoryID, r#type.groupID) {
+            (CATEGORY_IMPLANT_ID, GROUP_BOOSTER_ID) => return Ok(EftLine::Booster(module_type_id)),
+            (CATEGORY_IMPLANT_ID, _) => return Ok(EftLine::Implant(module_type_id)),
+            (_, GROUP_SHIP_MODIFIER_ID) => return Ok(EftLine::Mode(module_type_id)),
+            (CATEGORY_CHARGE_ID, _) => return Ok(EftLine::Charge(module_type_id, 1)),
             _ => {}
         }
     }
 
-    None
+    let charge_type_id = match charge_name {
+        Some(charge_name) => Some(lookup_type(info, charge_name)?),
+        None => None,
+    };
+
+    let Some((slot_type, index)) = find_slot_type_index(info, module_type_id, module_slots) else {
+        return Err(format!("Module '{}' does not fit in any slot", module_name));
+    };
+
+    Ok(EftLine::Module(data_types::EsfModule {
+        type_id: module_type_id,
+        slot: data_types::EsfSlot {
+            r#type: slot_type,
+            index,
+        },
+        state: state.unwrap_or(data_types::EsfState::Active),
+        charge: charge_type_id.map(|charge_type_id| data_types::EsfCharge {
+            type_id: charge_type_id,
+        }),
+    }))
+}
+
+/* Parse a quantity line: "<Type Name> x<Quantity>", optionally followed by a state marker. */
+fn load_quantity_line(
+    info: &impl InfoName,
+    line: &str,
+) -> Result<(i32, i32, Option<data_types::EsfState>), String> {
+    let (line, state) = split_state(line);
+    let Some((type_name, quantity)) = split_quantity(line) else {
+        return Err(format!(
+            "Expected '<Type Name> x<Quantity>', got '{}'",
+            line
+        ));
+    };
+
+    let quantity = match quantity.parse::<i32>() {
+        Ok(quantity) if quantity > 0 => quantity,
+        _ => return Err(format!("Invalid quantity '{}'", quantity)),
+    };
+
+    Ok((lookup_type(info, type_name)?, quantity, state))
 }
 
-/* Load an EFT string and return an ESF fit structure. */
-pub fn load_eft(info: &impl InfoName, eft: &String) -> Result<EftFit, String> {
-    let eft_lines: Vec<&str> = eft.lines().collect();
+/* Parse the header "[<Ship Type Name>, <Fit Name>]" into the ship type and name. */
+fn load_header(info: &impl InfoName, header: &str) -> Result<(i32, String), String> {
+    let Some(header) = header
+        .strip_prefix('[')
+        .and_then(|header| header.strip_suffix(']'))
+    else {
+        return Err("Invalid EFT header; expected '[<Ship Type>, <Fit Name>]'".to_string());
+    };
 
-    /* First line of an EFT always start with "[ship-type,name]". */
-    let header = eft_lines[0];
-    if !header.starts_with("[") || !header.ends_with("]") {
-        return Err("Invalid EFT header".to_string());
-    }
-    let header = header.trim_start_matches("[").trim_end_matches("]");
+    let (ship_type_name, name) = header.split_once(',').unwrap_or((header, ""));
+    let ship_type_id = lookup_type(info, ship_type_name.trim())?;
+
+    Ok((ship_type_id, name.trim().to_string()))
+}
+
+/* Parse the (numbered and trimmed) lines of a single EFT. */
+fn parse_eft_lines<'a>(
+    info: &impl InfoName,
+    eft_lines: impl Iterator<Item = (usize, &'a str)>,
+    mode: EftMode,
+) -> EftResult {
+    let mut diagnostics = Vec::new();
 
-    let header = header.split(",").collect::<Vec<&str>>();
-    let ship_type_name = header[0];
-    let name = header[1];
+    let mut eft_lines = eft_lines.skip_while(|(_, line)| line.is_empty());
+
+    /* First line of an EFT always start with "[ship-type,name]". */
+    let Some((header_line, header)) = eft_lines.next() else {
+        return Err(vec![EftDiagnostic {
+            line: 1,
+            reason: "Empty EFT".to_string(),
+        }]);
+    };
+    let (ship_type_id, name) = load_header(info, header).map_err(|reason| {
+        vec![EftDiagnostic {
+            line: header_line,
+            reason,
+        }]
+    })?;
 
     let mut eft_fit = EftFit {
-        name: name.to_string(),
+        name,
         esf_fit: data_types::EsfFit {
-            ship_type_id: info.type_name_to_id(ship_type_name),
+            ship_type_id,
             modules: Vec::new(),
             drones: Vec::new(),
+            implants: Vec::new(),
+            boosters: Vec::new(),
+            mode: None,
         },
         cargo: Vec::new(),
     };
 
+    let mut seen_quantity_section = false;
+
     /* An EFT has sections, which are seperated by a new line. */
     for section in section_iter(eft_lines) {
-        /* This is a module section if none of the strings end with "x<quantity>". */
-        let is_module_section = !section.iter().all(|line| {
-            let x_pos = line.find("x");
-            x_pos.map_or(false, |x_pos| {
-                line[x_pos + 1..].chars().all(|c| c.is_numeric())
-            })
-        });
-
-        match is_module_section {
-            true => {
-                let mut module_slots: HashMap<data_types::EsfSlotType, i32> = HashMap::new();
-
-                for line in section {
-                    let mut line = line.trim();
-                    let mut offline = false;
-
-                    if line.starts_with("[Empty") {
-                        let slot_type = match line {
-                            "[Empty High slot]" => data_types::EsfSlotType::High,
-                            "[Empty Med slot]" => data_types::EsfSlotType::Medium,
-                            "[Empty Low slot]" => data_types::EsfSlotType::Low,
-                            "[Empty Rig slot]" => data_types::EsfSlotType::Rig,
-                            "[Empty Subsystem slot]" => data_types::EsfSlotType::SubSystem,
-                            _ => panic!("Invalid slot type"),
-                        };
-
-                        let index = module_slots.entry(slot_type).or_insert(0);
-                        *index += 1;
-                        continue;
+        /* This is a module section if not all of the lines end with "x<quantity>". */
+        let is_module_section = !section
+            .iter()
+            .all(|(_, line)| split_quantity(split_state(line).0).is_some());
+
+        if is_module_section {
+            let mut module_slots: HashMap<data_types::EsfSlotType, i32> = HashMap::new();
+
+            /* A line that cannot be parsed (in lenient mode) still takes a slot of the section,
+             * so the modules after it keep their index. Until a line tells the slot type of the
+             * section, these are counted, and claimed once it is known. */
+            let mut section_slot_type = None;
+            let mut unclaimed_slots = 0;
+
+            for (line_number, line) in section {
+                let mut result = load_module_line(info, line, &mut module_slots);
+
+                let slot_type = match &result {
+                    Ok(EftLine::Module(module)) => Some(module.slot.r#type),
+                    Ok(EftLine::Empty(slot_type)) => Some(*slot_type),
+                    _ => None,
+                };
+                if let (Some(slot_type), None) = (slot_type, section_slot_type) {
+                    section_slot_type = Some(slot_type);
+                    *module_slots.entry(slot_type).or_insert(0) += unclaimed_slots;
+                    if let Ok(EftLine::Module(module)) = &mut result {
+                        module.slot.index += unclaimed_slots;
                     }
+                }
 
-                    if line.ends_with("/offline") {
-                        offline = true;
-                        line = line[..line.len() - 8].trim_end();
+                match result {
+                    Ok(EftLine::Empty(_)) => {}
+                    Ok(EftLine::Module(module)) => eft_fit.esf_fit.modules.push(module),
+                    Ok(EftLine::Implant(type_id)) => eft_fit
+                        .esf_fit
+                        .implants
+                        .push(data_types::EsfImplant { type_id }),
+                    Ok(EftLine::Booster(type_id)) => eft_fit
+                        .esf_fit
+                        .boosters
+                        .push(data_types::EsfBooster { type_id }),
+                    Ok(EftLine::Mode(type_id)) => {
+                        eft_fit.esf_fit.mode = Some(data_types::EsfMode { type_id })
                     }
-
-                    /* Can either be "<Module Name>" or "<Module Name>, <Charge Name>". */
-                    let comma_pos = line.find(",");
-
-                    let (module_name, charge_name) = match comma_pos {
-                        Some(comma_pos) => {
-                            let module_name = line[..comma_pos].trim();
-                            let charge_name = line[comma_pos + 1..].trim();
-                            (module_name, Some(charge_name))
-                        }
-                        None => {
-                            let module_name = line.trim();
-                            (module_name, None)
+                    Ok(EftLine::Charge(type_id, quantity)) => {
+                        eft_fit.cargo.push(EftCargo { type_id, quantity })
+                    }
+                    Err(reason) => {
+                        match section_slot_type {
+                            Some(slot_type) => *module_slots.entry(slot_type).or_insert(0) += 1,
+                            None => unclaimed_slots += 1,
                         }
-                    };
-
-                    let module_type_id = info.type_name_to_id(module_name);
-                    let charge_type_id =
-                        charge_name.map(|charge_name| info.type_name_to_id(charge_name));
+                        diagnostics.push(EftDiagnostic {
+                            line: line_number,
+                            reason,
+                        })
+                    }
+                }
+            }
+        } else {
+            let mut items = Vec::new();
+
+            for (line_number, line) in section {
+                match load_quantity_line(info, line) {
+                    Ok(item) => items.push(item),
+                    Err(reason) => diagnostics.push(EftDiagnostic {
+                        line: line_number,
+                        reason,
+                    }),
+                }
+            }
 
-                    let slot_type_index =
-                        find_slot_type_index(info, module_type_id, &mut module_slots);
-                    if slot_type_index.is_none() {
-                        return Err(format!("Module {} does not fit in any slot", module_name));
+            /* The client writes the drone bay before implants, boosters and cargo; so only the
+             * first quantity section can be the drone bay, and only if it holds nothing but
+             * drones. Any other quantity section is cargo, even if it holds only drones. */
+            let are_drones = !seen_quantity_section
+                && eft_fit.esf_fit.implants.is_empty()
+                && eft_fit.esf_fit.boosters.is_empty()
+                && items
+                    .iter()
+                    .all(|(type_id, _, _)| info.get_type(*type_id).categoryID == CATEGORY_DRONE_ID);
+            seen_quantity_section = true;
+
+            for (type_id, quantity, state) in items {
+                if are_drones {
+                    for _ in 0..quantity {
+                        eft_fit.esf_fit.drones.push(data_types::EsfDrone {
+                            type_id,
+                            state: state.unwrap_or(data_types::EsfState::Active),
+                        });
                     }
-                    let (slot_type, index) = slot_type_index.unwrap();
-
-                    let module = data_types::EsfModule {
-                        type_id: module_type_id,
-                        slot: data_types::EsfSlot {
-                            r#type: slot_type,
-                            index,
-                        },
-                        state: if offline {
-                            data_types::EsfState::Passive
-                        } else {
-                            data_types::EsfState::Active
-                        },
-                        charge: charge_type_id.map(|charge_type_id| data_types::EsfCharge {
-                            type_id: charge_type_id,
-                        }),
-                    };
-
-                    eft_fit.esf_fit.modules.push(module);
+                } else {
+                    eft_fit.cargo.push(EftCargo { type_id, quantity });
                 }
             }
-            false => {
-                let mut items = Vec::new();
+        }
+    }
 
-                let mut are_drones = true;
+    if mode == EftMode::Strict && !diagnostics.is_empty() {
+        return Err(diagnostics);
+    }
 
-                for line in section {
-                    /* Always in the form "<Type Name> x<Quantity>" */
-                    let x_pos = line.find("x").unwrap();
-                    let type_name = line[..x_pos].trim();
-                    let quantity = line[x_pos + 1..].parse::<i32>().unwrap();
+    Ok((eft_fit, diagnostics))
+}
 
-                    let type_id = info.type_name_to_id(type_name);
+fn numbered_lines(eft: &str) -> impl Iterator<Item = (usize, &str)> {
+    eft.lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+}
 
-                    let r#type = info.get_type(type_id);
-                    are_drones = are_drones && r#type.categoryID == 18; // Drone
+/* Whether a line is the "[ship-type,name]" header that starts an EFT. */
+fn is_header(line: &str) -> bool {
+    line.starts_with('[') && line.ends_with(']') && !line.starts_with("[Empty")
+}
 
-                    items.push((type_id, quantity));
-                }
+fn diagnostics_to_string(diagnostics: Vec<EftDiagnostic>) -> String {
+    diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic.to_string())
+        .collect::<Vec<String>>()
+        .join("\n")
+}
 
-                if are_drones {
-                    for (type_id, quantity) in items {
-                        for _ in 0..quantity {
-                            let drone = data_types::EsfDrone {
-                                type_id,
-                                state: data_types::EsfState::Active,
-                            };
-
-                            eft_fit.esf_fit.drones.push(drone);
-                        }
-                    }
-                } else {
-                    for (type_id, quantity) in items {
-                        let cargo = EftCargo { type_id, quantity };
+/* Parse an EFT string into an ESF fit structure, together with all problems
+ * found. In strict mode any problem is an error; in lenient mode lines with
+ * problems are skipped. An unusable header is always an error. */
+pub fn parse_eft(info: &impl InfoName, eft: &str, mode: EftMode) -> EftResult {
+    parse_eft_lines(info, numbered_lines(eft), mode)
+}
 
-                        eft_fit.cargo.push(cargo);
-                    }
+/* Parse a document with one or more EFTs after each other, like a doctrine file.
+ * Every "[ship-type,name]" header starts a new fit; each fit is parsed on its own,
+ * so a broken fit doesn't affect the others. Line numbers are those in the document. */
+pub fn parse_eft_document(info: &impl InfoName, eft: &str, mode: EftMode) -> Vec<EftResult> {
+    let mut blocks: Vec<Vec<(usize, &str)>> = Vec::new();
+
+    for (line_number, line) in numbered_lines(eft) {
+        match blocks.last_mut() {
+            Some(block) if !is_header(line) => block.push((line_number, line)),
+            _ => {
+                /* Empty lines before the first header are not a fit of their own. */
+                if blocks.is_empty() && line.is_empty() {
+                    continue;
                 }
+                blocks.push(vec![(line_number, line)]);
             }
         }
     }
 
-    Ok(eft_fit)
+    blocks
+        .into_iter()
+        .map(|block| parse_eft_lines(info, block.into_iter(), mode))
+        .collect()
+}
+
+/* Load an EFT string and return an ESF fit structure. Any problem in the EFT is an error. */
+pub fn load_eft(info: &impl InfoName, eft: &str) -> Result<EftFit, String> {
+    match parse_eft(info, eft, EftMode::Strict) {
+        Ok((eft_fit, _)) => Ok(eft_fit),
+        Err(diagnostics) => Err(diagnostics_to_string(diagnostics)),
+    }
+}
+
+/* Load a document with one or more EFTs, and return an ESF fit structure (or the problems) per fit. */
+pub fn load_eft_document(info: &impl InfoName, eft: &str) -> Vec<Result<EftFit, String>> {
+    parse_eft_document(info, eft, EftMode::Strict)
+        .into_iter()
+        .map(|result| match result {
+            Ok((eft_fit, _)) => Ok(eft_fit),
+            Err(diagnostics) => Err(diagnostics_to_string(diagnostics)),
+        })
+        .collect()
 }
@@ -0,0 +1,213 @@
+use crate::data_types;
+use crate::fit_common::type_name;
+use crate::info::InfoName;
+
+use super::{EftCargo, EftFit};
+
+/* Attributes on the hull that tell how many slots of each type it has. */
+const ATTRIBUTE_LOW_SLOTS_ID: i32 = 12;
+const ATTRIBUTE_MED_SLOTS_ID: i32 = 13;
+const ATTRIBUTE_HI_SLOTS_ID: i32 = 14;
+const ATTRIBUTE_RIG_SLOTS_ID: i32 = 1137;
+const ATTRIBUTE_MAX_SUBSYSTEMS_ID: i32 = 1367;
+const ATTRIBUTE_SERVICE_SLOTS_ID: i32 = 2056;
+
+/* Sections in the order the game client writes them. */
+const MODULE_SECTIONS: [(data_types::EsfSlotType, &str, i32); 6] = [
+    (
+        data_types::EsfSlotType::Low,
+        "[Empty Low slot]",
+        ATTRIBUTE_LOW_SLOTS_ID,
+    ),
+    (
+        data_types::EsfSlotType::Medium,
+        "[Empty Med slot]",
+        ATTRIBUTE_MED_SLOTS_ID,
+    ),
+    (
+        data_types::EsfSlotType::High,
+        "[Empty High slot]",
+        ATTRIBUTE_HI_SLOTS_ID,
+    ),
+    (
+        data_types::EsfSlotType::Rig,
+        "[Empty Rig slot]",
+        ATTRIBUTE_RIG_SLOTS_ID,
+    ),
+    (
+        data_types::EsfSlotType::SubSystem,
+        "[Empty Subsystem slot]",
+        ATTRIBUTE_MAX_SUBSYSTEMS_ID,
+    ),
+    (
+        data_types::EsfSlotType::Service,
+        "[Empty Service slot]",
+        ATTRIBUTE_SERVICE_SLOTS_ID,
+    ),
+];
+
+/* The state marker written after a line; Active is the default, so it has none. */
+fn state_marker(state: data_types::EsfState) -> &'static str {
+    match state {
+        data_types::EsfState::Passive => " /offline",
+        data_types::EsfState::Online => " /online",
+        data_types::EsfState::Active => "",
+        data_types::EsfState::Overload => " /overload",
+    }
+}
+
+fn hull_slots(info: &impl InfoName, ship_type_id: i32, attribute_id: i32) -> i32 {
+    info.get_dogma_attributes(ship_type_id)
+        .iter()
+        .find(|attribute| attribute.attributeID == attribute_id)
+        .map_or(0, |attribute| attribute.value as i32)
+}
+
+/* Write a module section; gaps, and slots the hull has but are not used, become "[Empty X slot]". */
+fn module_section(
+    info: &impl InfoName,
+    esf_fit: &data_types::EsfFit,
+    slot_type: &data_types::EsfSlotType,
+    empty: &str,
+    slots_attribute_id: i32,
+) -> Result<Vec<String>, String> {
+    let modules: Vec<&data_types::EsfModule> = esf_fit
+        .modules
+        .iter()
+        .filter(|module| module.slot.r#type == *slot_type)
+        .collect();
+
+    let slots = modules
+        .iter()
+        .map(|module| module.slot.index + 1)
+        .max()
+        .unwrap_or(0)
+        .max(hull_slots(info, esf_fit.ship_type_id, slots_attribute_id));
+
+    let mut lines = Vec::new();
+    for index in 0..slots {
+        let module = modules.iter().find(|module| module.slot.index == index);
+
+        let Some(module) = module else {
+            lines.push(empty.to_string());
+            continue;
+        };
+
+        let mut line = type_name(info, module.type_id)?;
+        if let Some(charge) = &module.charge {
+            line = format!("{}, {}", line, type_name(info, charge.type_id)?);
+        }
+        line += state_marker(module.state);
+
+        lines.push(line);
+    }
+
+    Ok(lines)
+}
+
+/* Write a list of "<Type Name> x<Quantity>" lines, keeping the order types were first seen in.
+ * Items of the same type but with a different state are written on their own line. */
+fn quantity_section(
+    info: &impl InfoName,
+    items: impl Iterator<Item = (i32, i32, data_types::EsfState)>,
+) -> Result<Vec<String>, String> {
+    let mut quantities: Vec<(i32, i32, data_types::EsfState)> = Vec::new();
+
+    for (type_id, quantity, state) in items {
+        match quantities
+            .iter_mut()
+            .find(|(id, _, s)| *id == type_id && *s == state)
+        {
+            Some((_, total, _)) => *total += quantity,
+            None => quantities.push((type_id, quantity, state)),
+        }
+    }
+
+    quantities
+        .into_iter()
+        .map(|(type_id, quantity, state)| {
+            Ok(format!(
+                "{} x{}{}",
+                type_name(info, type_id)?,
+                quantity,
+                state_marker(state)
+            ))
+        })
+        .collect()
+}
+
+/* Write an ESF fit (with cargo) as EFT string, the same way the game client does. */
+pub fn save_esf_fit(
+    info: &impl InfoName,
+    name: &str,
+    esf_fit: &data_types::EsfFit,
+    cargo: &[EftCargo],
+) -> Result<String, String> {
+    let mut eft = format!("[{}, {}]\n", type_name(info, esf_fit.ship_type_id)?, name);
+
+    for (slot_type, empty, slots_attribute_id) in &MODULE_SECTIONS {
+        let lines = module_section(info, esf_fit, slot_type, empty, *slots_attribute_id)?;
+        if lines.is_empty() {
+            continue;
+        }
+
+        eft += &lines.join("\n");
+        eft += "\n\n";
+    }
+
+    if let Some(mode) = &esf_fit.mode {
+        eft += &type_name(info, mode.type_id)?;
+        eft += "\n\n";
+    }
+
+    /* The client separates drones and cargo from the modules with an additional empty line. */
+    eft += "\n";
+
+    let drones = quantity_section(
+        info,
+        esf_fit
+            .drones
+            .iter()
+            .map(|drone| (drone.type_id, 1, drone.state)),
+    )?;
+    if !drones.is_empty() {
+        eft += &drones.join("\n");
+        eft += "\n\n";
+    }
+
+    /* Implants and boosters have no quantity; they are listed one per line. */
+    let implants = esf_fit.implants.iter().map(|implant| implant.type_id);
+    let boosters = esf_fit.boosters.iter().map(|booster| booster.type_id);
+    for type_ids in [implants.collect::<Vec<i32>>(), boosters.collect()] {
+        if type_ids.is_empty() {
+            continue;
+        }
+
+        let lines = type_ids
+            .into_iter()
+            .map(|type_id| type_name(info, type_id))
+            .collect::<Result<Vec<String>, String>>()?;
+        eft += &lines.join("\n");
+        eft += "\n\n";
+    }
+
+    /* Without drones, implants or boosters before it, a cargo of only drones reads back as
+     * the drone bay; EFT has no way to tell these apart. */
+    let cargo = quantity_section(
+        info,
+        cargo
+            .iter()
+            .map(|cargo| (cargo.type_id, cargo.quantity, data_types::EsfState::Active)),
+    )?;
+    if !cargo.is_empty() {
+        eft += &cargo.join("\n");
+        eft += "\n";
+    }
+
+    Ok(eft.trim_end().to_string() + "\n")
+}
+
+/* Write an EFT fit back to an EFT string. */
+pub fn save_eft(info: &impl InfoName, eft_fit: &EftFit) -> Result<String, String> {
+    save_esf_fit(info, &eft_fit.name, &eft_fit.esf_fit, &eft_fit.cargo)
+}
@@ -0,0 +1,169 @@
+use serde::{Deserialize, Serialize};
+
+use super::{EsiFlag, EsiLocation};
+use crate::data_types;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EsiFittingItem {
+    pub type_id: i32,
+    pub flag: EsiFlag,
+    pub quantity: i32,
+}
+
+/* A fitting as ESI returns it from, and accepts it on, /characters/{character_id}/fittings/. */
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EsiFitting {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub ship_type_id: i32,
+    pub items: Vec<EsiFittingItem>,
+}
+
+pub struct EsiFit {
+    pub name: String,
+    pub description: String,
+    pub esf_fit: data_types::EsfFit,
+    pub cargo: Vec<data_types::EsfCargo>,
+    /* Items that were left out, as the fit has no room for them (like a fighter bay). */
+    pub skipped: Vec<String>,
+}
+
+impl EsiFitting {
+    /* Convert to an ESF fit. ESI fittings don't tell which charge is loaded in
+     * which module, so charges stay in the cargo. Modules are set to Active.
+     * Items with an inventory flag that isn't part of a fit are skipped. */
+    pub fn to_esf_fit(&self) -> EsiFit {
+        let mut esi_fit = EsiFit {
+            name: self.name.clone(),
+            description: self.description.clone(),
+            esf_fit: data_types::EsfFit {
+                ship_type_id: self.ship_type_id,
+                modules: Vec::new(),
+                drones: Vec::new(),
+                implants: Vec::new(),
+                boosters: Vec::new(),
+                mode: None,
+            },
+            cargo: Vec::new(),
+            skipped: Vec::new(),
+        };
+
+        for item in &self.items {
+            let location = match item.flag.location() {
+                Ok(location) => location,
+                Err(error) => {
+                    esi_fit
+                        .skipped
+                        .push(format!("Skipped type {}: {}", item.type_id, error));
+                    continue;
+                }
+            };
+
+            match location {
+                EsiLocation::Slot(slot_type, index) => {
+                    esi_fit.esf_fit.modules.push(data_types::EsfModule {
+                        type_id: item.type_id,
+                        slot: data_types::EsfSlot {
+                            r#type: slot_type,
+                            index,
+                        },
+                        state: data_types::EsfState::Active,
+                        charge: None,
+                    });
+                }
+                EsiLocation::DroneBay => {
+                    for _ in 0..item.quantity {
+                        esi_fit.esf_fit.drones.push(data_types::EsfDrone {
+                            type_id: item.type_id,
+                            state: data_types::EsfState::Active,
+                        });
+                    }
+                }
+                EsiLocation::Cargo => esi_fit.cargo.push(data_types::EsfCargo {
+                    type_id: item.type_id,
+                    quantity: item.quantity,
+                }),
+                EsiLocation::Implant => esi_fit.esf_fit.implants.push(data_types::EsfImplant {
+                    type_id: item.type_id,
+                }),
+                EsiLocation::Booster => esi_fit.esf_fit.boosters.push(data_types::EsfBooster {
+                    type_id: item.type_id,
+                }),
+            }
+        }
+
+        esi_fit
+    }
+
+    /* Convert from an ESF fit. ESI fittings have no room for module states,
+     * implants, boosters or ship modes; those are dropped. Loaded charges are
+     * written as cargo, the same as the game client saves them. */
+    pub fn from_esf_fit(
+        name: &str,
+        description: &str,
+        esf_fit: &data_types::EsfFit,
+        cargo: &[data_types::EsfCargo],
+    ) -> Result<EsiFitting, String> {
+        let mut items = Vec::new();
+
+        for module in &esf_fit.modules {
+            items.push(EsiFittingItem {
+                type_id: module.type_id,
+                flag: EsiFlag::from_slot(&module.slot)?,
+                quantity: 1,
+            });
+        }
+
+        let mut add_quantity = |type_id: i32, flag: &str, quantity: i32| {
+            let existing = items.iter_mut().find(|item| {
+                item.type_id == type_id && matches!(&item.flag, EsiFlag::Name(name) if name == flag)
+            });
+            match existing {
+                Some(item) => item.quantity += quantity,
+                None => items.push(EsiFittingItem {
+                    type_id,
+                    flag: EsiFlag::Name(flag.to_string()),
+                    quantity,
+                }),
+            }
+        };
+
+        for drone in &esf_fit.drones {
+            add_quantity(drone.type_id, "DroneBay", 1);
+        }
+        for module in &esf_fit.modules {
+            if let Some(charge) = &module.charge {
+                add_quantity(charge.type_id, "Cargo", 1);
+            }
+        }
+        for cargo in cargo {
+            add_quantity(cargo.type_id, "Cargo", cargo.quantity);
+        }
+
+        Ok(EsiFitting {
+            name: name.to_string(),
+            description: description.to_string(),
+            ship_type_id: esf_fit.ship_type_id,
+            items,
+        })
+    }
+}
+
+/* Load an ESI fitting JSON string and return an ESF fit structure. */
+pub fn load_esi_fitting(json: &str) -> Result<EsiFit, String> {
+    let esi_fitting: EsiFitting =
+        serde_json::from_str(json).map_err(|e| format!("Invalid ESI fitting: {}", e))?;
+    Ok(esi_fitting.to_esf_fit())
+}
+
+/* Save an ESF fit (with cargo) as ESI fitting JSON string. */
+pub fn save_esi_fitting(
+    name: &str,
+    description: &str,
+    esf_fit: &data_types::EsfFit,
+    cargo: &[data_types::EsfCargo],
+) -> Result<String, String> {
+    let esi_fitting = EsiFitting::from_esf_fit(name, description, esf_fit, cargo)?;
+    serde_json::to_string(&esi_fitting).map_err(|e| format!("Cannot write ESI fitting: {}", e))
+}
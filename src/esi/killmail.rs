@@ -0,0 +1,147 @@
+use serde::Deserialize;
+
+use super::{EsiFit, EsiFlag, EsiLocation};
+use crate::data_types;
+use crate::fit_common::{CATEGORY_CHARGE_ID, GROUP_BOOSTER_ID};
+use crate::info::Info;
+
+#[derive(Deserialize, Debug)]
+pub struct EsiKillmailItem {
+    pub item_type_id: i32,
+    pub flag: i32,
+    #[serde(default)]
+    pub quantity_destroyed: i32,
+    #[serde(default)]
+    pub quantity_dropped: i32,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct EsiKillmailVictim {
+    pub ship_type_id: i32,
+    #[serde(default)]
+    pub items: Vec<EsiKillmailItem>,
+}
+
+/* A killmail as ESI returns it from /killmails/{killmail_id}/{killmail_hash}/;
+ * only the parts needed to reconstruct the fit of the victim. */
+#[derive(Deserialize, Debug)]
+pub struct EsiKillmail {
+    pub killmail_id: i64,
+    pub victim: EsiKillmailVictim,
+}
+
+impl EsiKillmailItem {
+    fn quantity(&self) -> i32 {
+        self.quantity_destroyed + self.quantity_dropped
+    }
+}
+
+fn add_cargo(cargo: &mut Vec<data_types::EsfCargo>, type_id: i32, quantity: i32) {
+    match cargo.iter_mut().find(|cargo| cargo.type_id == type_id) {
+        Some(cargo) => cargo.quantity += quantity,
+        None => cargo.push(data_types::EsfCargo { type_id, quantity }),
+    }
+}
+
+impl EsiKillmail {
+    /* Reconstruct the fit of the victim. A killmail lists a loaded charge as
+     * a separate item with the same flag as its module; the two are paired up
+     * again here, and a charge without module is moved to the cargo. Items in any
+     * other hold (fleet hangar, ore hold, containers in cargo, ..) are not part of
+     * the fit; they are skipped. */
+    pub fn to_esf_fit(&self, info: &impl Info) -> Result<EsiFit, String> {
+        let mut esi_fit = EsiFit {
+            name: format!("Killmail {}", self.killmail_id),
+            description: String::new(),
+            esf_fit: data_types::EsfFit {
+                ship_type_id: self.victim.ship_type_id,
+                modules: Vec::new(),
+                drones: Vec::new(),
+                implants: Vec::new(),
+                boosters: Vec::new(),
+                mode: None,
+            },
+            cargo: Vec::new(),
+            skipped: Vec::new(),
+        };
+        let mut charges = Vec::new();
+
+        for item in &self.victim.items {
+            let location = match EsiFlag::Id(item.flag).location() {
+                Ok(location) => location,
+                Err(error) => {
+                    esi_fit
+                        .skipped
+                        .push(format!("Skipped type {}: {}", item.item_type_id, error));
+                    continue;
+                }
+            };
+
+            match location {
+                EsiLocation::Slot(slot_type, index) => {
+                    if info.get_type(item.item_type_id).categoryID == CATEGORY_CHARGE_ID {
+                        charges.push((slot_type, index, item.item_type_id, item.quantity()));
+                        continue;
+                    }
+
+                    esi_fit.esf_fit.modules.push(data_types::EsfModule {
+                        type_id: item.item_type_id,
+                        slot: data_types::EsfSlot {
+                            r#type: slot_type,
+                            index,
+                        },
+                        state: data_types::EsfState::Active,
+                        charge: None,
+                    });
+                }
+                EsiLocation::DroneBay => {
+                    for _ in 0..item.quantity() {
+                        esi_fit.esf_fit.drones.push(data_types::EsfDrone {
+                            type_id: item.item_type_id,
+                            state: data_types::EsfState::Active,
+                        });
+                    }
+                }
+                EsiLocation::Cargo => {
+                    add_cargo(&mut esi_fit.cargo, item.item_type_id, item.quantity())
+                }
+                /* Killmails are not consistent in which of the two flags a
+                 * booster gets; go by the group instead. */
+                EsiLocation::Implant | EsiLocation::Booster => {
+                    if info.get_type(item.item_type_id).groupID == GROUP_BOOSTER_ID {
+                        esi_fit.esf_fit.boosters.push(data_types::EsfBooster {
+                            type_id: item.item_type_id,
+                        });
+                    } else {
+                        esi_fit.esf_fit.implants.push(data_types::EsfImplant {
+                            type_id: item.item_type_id,
+                        });
+                    }
+                }
+            }
+        }
+
+        /* A charge whose module was destroyed (or isn't known) ends up in the cargo. */
+        for (slot_type, index, type_id, quantity) in charges {
+            let module = esi_fit
+                .esf_fit
+                .modules
+                .iter_mut()
+                .find(|module| module.slot.r#type == slot_type && module.slot.index == index);
+
+            match module {
+                Some(module) => module.charge = Some(data_types::EsfCharge { type_id }),
+                None => add_cargo(&mut esi_fit.cargo, type_id, quantity),
+            }
+        }
+
+        Ok(esi_fit)
+    }
+}
+
+/* Load an ESI killmail JSON string and return the ESF fit of the victim. */
+pub fn load_killmail(info: &impl Info, json: &str) -> Result<EsiFit, String> {
+    let killmail: EsiKillmail =
+        serde_json::from_str(json).map_err(|e| format!("Invalid killmail: {}", e))?;
+    killmail.to_esf_fit(info)
+}
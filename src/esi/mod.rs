@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+
+use crate::data_types;
+
+mod fitting;
+mod killmail;
+
+pub use fitting::{load_esi_fitting, save_esi_fitting, EsiFit, EsiFitting, EsiFittingItem};
+pub use killmail::{load_killmail, EsiKillmail, EsiKillmailItem, EsiKillmailVictim};
+
+/* ESI gives inventory flags either by name ("HiSlot0") or, in older
+ * endpoints, by their numeric ID (27). */
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum EsiFlag {
+    Name(String),
+    Id(i32),
+}
+
+/* Where an item with a given inventory flag ends up in a fit. */
+#[derive(Debug, PartialEq)]
+pub enum EsiLocation {
+    Slot(data_types::EsfSlotType, i32),
+    DroneBay,
+    Cargo,
+    Implant,
+    Booster,
+}
+
+/* (name prefix, slot type, first numeric ID, number of slots) of all slot-based inventory flags. */
+const SLOT_FLAGS: [(&str, data_types::EsfSlotType, i32, i32); 6] = [
+    ("LoSlot", data_types::EsfSlotType::Low, 11, 8),
+    ("MedSlot", data_types::EsfSlotType::Medium, 19, 8),
+    ("HiSlot", data_types::EsfSlotType::High, 27, 8),
+    ("RigSlot", data_types::EsfSlotType::Rig, 92, 3),
+    ("SubSystemSlot", data_types::EsfSlotType::SubSystem, 125, 4),
+    ("ServiceSlot", data_types::EsfSlotType::Service, 164, 8),
+];
+
+const FLAG_CARGO_ID: i32 = 5;
+const FLAG_DRONE_BAY_ID: i32 = 87;
+const FLAG_BOOSTER_ID: i32 = 88;
+const FLAG_IMPLANT_ID: i32 = 89;
+
+impl EsiFlag {
+    pub fn location(&self) -> Result<EsiLocation, String> {
+        match self {
+            EsiFlag::Name(name) => match name.as_str() {
+                "Cargo" => Ok(EsiLocation::Cargo),
+                "DroneBay" => Ok(EsiLocation::DroneBay),
+                "Booster" => Ok(EsiLocation::Booster),
+                "Implant" => Ok(EsiLocation::Implant),
+                _ => SLOT_FLAGS
+                    .iter()
+                    .find_map(|(prefix, slot_type, _, slots)| {
+                        let index = name.strip_prefix(prefix)?.parse::<i32>().ok()?;
+                        (0..*slots)
+                            .contains(&index)
+                            .then_some(EsiLocation::Slot(*slot_type, index))
+                    })
+                    .ok_or(format!("Unsupported inventory flag '{}'", name)),
+            },
+            EsiFlag::Id(id) => match *id {
+                FLAG_CARGO_ID => Ok(EsiLocation::Cargo),
+                FLAG_DRONE_BAY_ID => Ok(EsiLocation::DroneBay),
+                FLAG_BOOSTER_ID => Ok(EsiLocation::Booster),
+                FLAG_IMPLANT_ID => Ok(EsiLocation::Implant),
+                _ => SLOT_FLAGS
+                    .iter()
+                    .find(|(_, _, first, slots)| (*first..*first + *slots).contains(id))
+                    .map(|(_, slot_type, first, _)| EsiLocation::Slot(*slot_type, id - first))
+                    .ok_or(format!("Unsupported inventory flag {}", id)),
+            },
+        }
+    }
+
+    /* Named flag for a slot; ESI fittings always use names. ESI has a fixed number of
+     * flags per slot type, so a slot beyond those cannot be written. */
+    pub fn from_slot(slot: &data_types::EsfSlot) -> Result<EsiFlag, String> {
+        let (prefix, _, _, slots) = SLOT_FLAGS
+            .iter()
+            .find(|(_, slot_type, _, _)| *slot_type == slot.r#type)
+            .unwrap();
+        if !(0..*slots).contains(&slot.index) {
+            return Err(format!(
+                "Slot index {} is out of range for {} (0-{})",
+                slot.index,
+                prefix,
+                slots - 1
+            ));
+        }
+        Ok(EsiFlag::Name(format!("{}{}", prefix, slot.index)))
+    }
+}
@@ -0,0 +1,67 @@
+use std::collections::HashMap;
+
+use crate::data_types;
+use crate::info::{Info, InfoName};
+
+/* Categories and groups the fit formats need to tell modules, charges, drones, implants,
+ * boosters and ship modes apart. */
+pub const CATEGORY_CHARGE_ID: i32 = 8;
+pub const CATEGORY_DRONE_ID: i32 = 18;
+pub const CATEGORY_IMPLANT_ID: i32 = 20;
+pub const GROUP_BOOSTER_ID: i32 = 303;
+pub const GROUP_SHIP_MODIFIER_ID: i32 = 1306;
+
+/* (slot effect, slot type) of all module slots. */
+const SLOT_EFFECTS: [(i32, data_types::EsfSlotType); 6] = [
+    (11, data_types::EsfSlotType::Low),
+    (12, data_types::EsfSlotType::High),
+    (13, data_types::EsfSlotType::Medium),
+    (2663, data_types::EsfSlotType::Rig),
+    (3772, data_types::EsfSlotType::SubSystem),
+    (6306, data_types::EsfSlotType::Service),
+];
+
+pub fn lookup_type(info: &impl InfoName, type_name: &str) -> Result<i32, String> {
+    match info.type_name_to_id(type_name) {
+        0 => {
+            let suggestions = info.type_name_suggestions(type_name);
+            if suggestions.is_empty() {
+                Err(format!("Unknown type '{}'", type_name))
+            } else {
+                Err(format!(
+                    "Unknown type '{}'; did you mean '{}'?",
+                    type_name,
+                    suggestions.join("', '")
+                ))
+            }
+        }
+        type_id => Ok(type_id),
+    }
+}
+
+pub fn type_name(info: &impl InfoName, type_id: i32) -> Result<String, String> {
+    info.type_id_to_name(type_id)
+        .ok_or(format!("Unknown type ID {}", type_id))
+}
+
+/* Find the slot type of a module by its slot effect, and claim the next free index of that slot type. */
+pub fn find_slot_type_index(
+    info: &impl Info,
+    type_id: i32,
+    module_slots: &mut HashMap<data_types::EsfSlotType, i32>,
+) -> Option<(data_types::EsfSlotType, i32)> {
+    let effects = info.get_dogma_effects(type_id);
+
+    for effect in &effects {
+        if let Some((_, slot_type)) = SLOT_EFFECTS
+            .iter()
+            .find(|(effect_id, _)| *effect_id == effect.effectID)
+        {
+            let index = module_slots.entry(*slot_type).or_insert(0);
+            *index += 1;
+            return Some((*slot_type, *index - 1));
+        }
+    }
+
+    None
+}
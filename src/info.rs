@@ -1,21 +1,51 @@
-use std::collections::BTreeMap;
-
 use crate::data_types;
 
-pub trait Info {
-    fn skills(&self) -> &BTreeMap<i32, i32>;
-    fn fit(&self) -> &data_types::EsfFit;
+const ATTRIBUTE_META_LEVEL_ID: i32 = 633;
 
+/* Access to the static data (SDE). A single provider can serve any number
+ * of calculations; the fit and skills are passed to calculate() instead. */
+pub trait Info {
     fn get_dogma_attributes(&self, type_id: i32) -> Vec<data_types::TypeDogmaAttribute>;
     fn get_dogma_attribute(&self, attribute_id: i32) -> data_types::DogmaAttribute;
     fn get_dogma_effects(&self, type_id: i32) -> Vec<data_types::TypeDogmaEffect>;
     fn get_dogma_effect(&self, effect_id: i32) -> data_types::DogmaEffect;
     fn get_type(&self, type_id: i32) -> data_types::Type;
     fn attribute_name_to_id(&self, name: &str) -> i32;
+    fn attribute_id_to_name(&self, _attribute_id: i32) -> Option<String> {
+        None
+    }
+
+    /* Names of groups and categories, for human-readable output; None if not available.
+     * EVEShipFit's data files don't have these: with the protobuf files, they come from a
+     * typeMetadata.json that has to be put next to them by hand; the SDE has them. */
+    fn get_group_name(&self, _group_id: i32) -> Option<String> {
+        None
+    }
+    fn get_category_name(&self, _category_id: i32) -> Option<String> {
+        None
+    }
+    /* Meta level of a type, from its "metaLevelOld" attribute. */
+    fn get_meta_level(&self, type_id: i32) -> Option<i32> {
+        self.get_dogma_attributes(type_id)
+            .iter()
+            .find(|attribute| attribute.attributeID == ATTRIBUTE_META_LEVEL_ID)
+            .map(|attribute| attribute.value as i32)
+    }
 }
 
-pub trait InfoName {
-    fn get_dogma_effects(&self, type_id: i32) -> Vec<data_types::TypeDogmaEffect>;
-    fn get_type(&self, type_id: i32) -> data_types::Type;
+/* Name lookups on top of the static data, used to import / export fits. */
+pub trait InfoName: Info {
     fn type_name_to_id(&self, name: &str) -> i32;
+    fn type_id_to_name(&self, type_id: i32) -> Option<String>;
+    /* Names close to an unknown name, best first; for "did you mean" hints. */
+    fn type_name_suggestions(&self, _name: &str) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+/* Skill data on top of the static data, used to build skill profiles. */
+pub trait InfoSkills: Info {
+    fn skill_type_ids(&self) -> Vec<i32>;
+    /* Highest level an Alpha clone can train the skill to; 0 if it cannot. */
+    fn alpha_skill_level(&self, type_id: i32) -> i32;
 }
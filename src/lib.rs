@@ -1,10 +1,27 @@
 pub mod calculate;
 pub mod data_types;
 pub mod info;
+pub mod skills;
+
+#[cfg(feature = "dna")]
+pub mod dna;
+
+#[cfg(any(feature = "dna", feature = "eft", feature = "esi", feature = "xml"))]
+#[cfg_attr(
+    not(all(feature = "dna", feature = "eft", feature = "esi", feature = "xml")),
+    allow(dead_code)
+)]
+mod fit_common;
 
 #[cfg(feature = "eft")]
 pub mod eft;
 
+#[cfg(feature = "esi")]
+pub mod esi;
+
+#[cfg(feature = "xml")]
+pub mod xml;
+
 #[cfg(feature = "wasm")]
 mod wasm;
 
@@ -0,0 +1,65 @@
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use super::{Data, InfoMain};
+use crate::calculate::{self, Input, Ship};
+
+fn panic_to_string(payload: Box<dyn std::any::Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "Calculation panicked".to_string()
+    }
+}
+
+pub(super) fn calculate_job(info: &InfoMain, input: &Input) -> Result<Ship, String> {
+    let ship_type_id = input.fit.ship_type_id;
+    if !info.data.types.contains_key(&ship_type_id) {
+        return Err(format!("Unknown ship type ID {}", ship_type_id));
+    }
+
+    /* A broken fit should only fail its own job, not the whole batch. */
+    panic::catch_unwind(AssertUnwindSafe(|| calculate::calculate(info, input)))
+        .map_err(panic_to_string)
+}
+
+/* Calculate a list of inputs against a single Data set, spread over a pool of
+ * threads. Results are returned in the same order as the inputs. When threads
+ * is None, the available parallelism of the machine is used. */
+pub fn calculate_batch(
+    data: &Data,
+    inputs: &[Input],
+    threads: Option<usize>,
+) -> Vec<Result<Ship, String>> {
+    let threads = threads
+        .unwrap_or_else(|| thread::available_parallelism().map_or(1, |n| n.get()))
+        .clamp(1, inputs.len().max(1));
+
+    let info = InfoMain::new(data);
+    let results: Vec<Mutex<Option<Result<Ship, String>>>> =
+        inputs.iter().map(|_| Mutex::new(None)).collect();
+    let next_input = AtomicUsize::new(0);
+
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| loop {
+                let index = next_input.fetch_add(1, Ordering::Relaxed);
+                if index >= inputs.len() {
+                    break;
+                }
+
+                let result = calculate_job(&info, &inputs[index]);
+                *results[index].lock().unwrap() = Some(result);
+            });
+        }
+    });
+
+    results
+        .into_iter()
+        .map(|result| result.into_inner().unwrap().unwrap())
+        .collect()
+}
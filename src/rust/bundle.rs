@@ -0,0 +1,273 @@
+use std::path::Path;
+
+use super::esf_data;
+use super::protobuf::{decode_protobuf, parse_clone_grades, parse_json, read_file};
+use super::Data;
+
+/* A bundle is a single file with all data files in it:
+ *
+ *   "ESFDATA\0"                      magic
+ *   u32 (little endian)              format version
+ *   u32 (little endian)              SDE build number
+ *   u32 (little endian)              number of sections
+ *   per section:
+ *     u8 + bytes                     file name
+ *     u32 (little endian) + bytes    file content
+ *
+ * The four protobuf files are required; "cloneGrades.json", "typeNames.json" and
+ * "typeMetadata.json" are optional. */
+const BUNDLE_MAGIC: &[u8; 8] = b"ESFDATA\0";
+const BUNDLE_VERSION: u32 = 1;
+
+const REQUIRED_FILES: [&str; 4] = [
+    "types.pb2",
+    "typeDogma.pb2",
+    "dogmaAttributes.pb2",
+    "dogmaEffects.pb2",
+];
+const OPTIONAL_FILES: [&str; 3] = ["cloneGrades.json", "typeNames.json", "typeMetadata.json"];
+
+#[cfg(feature = "embedded-data")]
+static EMBEDDED_BUNDLE: &[u8] = include_bytes!(env!("ESF_DATA_BUNDLE"));
+
+struct BundleReader<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> BundleReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        if self.buf.len() < len {
+            return Err("Corrupt bundle: unexpected end of file".to_string());
+        }
+
+        let (bytes, rest) = self.buf.split_at(len);
+        self.buf = rest;
+        Ok(bytes)
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes(bytes.try_into().unwrap()))
+    }
+}
+
+/* Header and sections of a bundle, without decoding the sections. */
+struct Bundle<'a> {
+    sde_build: u32,
+    sections: Vec<(&'a str, &'a [u8])>,
+}
+
+fn read_bundle(bundle: &[u8]) -> Result<Bundle<'_>, String> {
+    let mut reader = BundleReader { buf: bundle };
+
+    if reader.take(BUNDLE_MAGIC.len()).ok() != Some(BUNDLE_MAGIC.as_slice()) {
+        return Err("Not a data bundle".to_string());
+    }
+    let version = reader.u32()?;
+    if version != BUNDLE_VERSION {
+        return Err(format!(
+            "Unsupported bundle version {}; expected {}",
+            version, BUNDLE_VERSION
+        ));
+    }
+    let sde_build = reader.u32()?;
+
+    let count = reader.u32()?;
+    let mut sections = Vec::new();
+    for _ in 0..count {
+        let name_len = reader.take(1)?[0] as usize;
+        let name = std::str::from_utf8(reader.take(name_len)?)
+            .map_err(|_| "Corrupt bundle: invalid section name".to_string())?;
+        let len = reader.u32()? as usize;
+        sections.push((name, reader.take(len)?));
+    }
+
+    Ok(Bundle {
+        sde_build,
+        sections,
+    })
+}
+
+/* Pack the data files in the given directory into a single bundle. */
+pub fn pack_bundle(path: &Path, sde_build: u32) -> Result<Vec<u8>, String> {
+    let mut sections = Vec::new();
+    for name in REQUIRED_FILES {
+        sections.push((name, read_file(path, name)?));
+    }
+    /* Only a missing optional file is left out; one that cannot be read is an error. */
+    for name in OPTIONAL_FILES {
+        if path.join(name).exists() {
+            sections.push((name, read_file(path, name)?));
+        }
+    }
+
+    let mut bundle = Vec::new();
+    bundle.extend_from_slice(BUNDLE_MAGIC);
+    bundle.extend_from_slice(&BUNDLE_VERSION.to_le_bytes());
+    bundle.extend_from_slice(&sde_build.to_le_bytes());
+    bundle.extend_from_slice(&(sections.len() as u32).to_le_bytes());
+
+    for (name, content) in sections {
+        let content_len = u32::try_from(content.len())
+            .map_err(|_| format!("{} is too large for a bundle", name))?;
+
+        bundle.push(name.len() as u8);
+        bundle.extend_from_slice(name.as_bytes());
+        bundle.extend_from_slice(&content_len.to_le_bytes());
+        bundle.extend_from_slice(&content);
+    }
+
+    Ok(bundle)
+}
+
+/* SDE build number of a bundle, without loading it. */
+pub fn bundle_sde_build(bundle: &[u8]) -> Result<u32, String> {
+    read_bundle(bundle).map(|bundle| bundle.sde_build)
+}
+
+impl Data {
+    /* Load the data from a bundle, as created by pack_bundle(). */
+    pub fn from_bundle(bundle: &[u8]) -> Result<Data, String> {
+        let bundle = read_bundle(bundle)?;
+
+        let section = |name: &str| {
+            bundle
+                .sections
+                .iter()
+                .find(|(section_name, _)| *section_name == name)
+                .map(|(_, content)| *content)
+        };
+        let required = |name: &str| section(name).ok_or(format!("Bundle has no {}", name));
+
+        let types: esf_data::Types = decode_protobuf(required("types.pb2")?, "types.pb2")?;
+        let type_dogma: esf_data::TypeDogma =
+            decode_protobuf(required("typeDogma.pb2")?, "typeDogma.pb2")?;
+        let dogma_attributes: esf_data::DogmaAttributes =
+            decode_protobuf(required("dogmaAttributes.pb2")?, "dogmaAttributes.pb2")?;
+        let dogma_effects: esf_data::DogmaEffects =
+            decode_protobuf(required("dogmaEffects.pb2")?, "dogmaEffects.pb2")?;
+
+        let mut data = Data::from_entries(
+            types.entries,
+            type_dogma.entries,
+            dogma_attributes.entries,
+            dogma_effects.entries,
+            section("cloneGrades.json")
+                .map(|json| parse_clone_grades(json, "cloneGrades.json"))
+                .transpose()?
+                .unwrap_or_default(),
+            section("typeNames.json")
+                .map(|json| parse_json(json, "typeNames.json"))
+                .transpose()?
+                .unwrap_or_default(),
+            section("typeMetadata.json")
+                .map(|json| parse_json(json, "typeMetadata.json"))
+                .transpose()?
+                .unwrap_or_default(),
+        );
+        data.sde_build = Some(bundle.sde_build);
+
+        Ok(data)
+    }
+
+    /* Load the bundle that was embedded at build time; the bundle is given with the
+     * ESF_DATA_BUNDLE environment variable (absolute path) when building. */
+    #[cfg(feature = "embedded-data")]
+    pub fn embedded() -> Result<Data, String> {
+        Data::from_bundle(EMBEDDED_BUNDLE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_data::test_data_dir;
+    use super::*;
+
+    #[test]
+    fn pack_and_load_keeps_the_data() {
+        let dir = test_data_dir("bundle-round-trip", true);
+        let data = Data::new(&dir.path).unwrap();
+        let bundle = pack_bundle(&dir.path, 2_500_000).unwrap();
+        let loaded = Data::from_bundle(&bundle).unwrap();
+
+        assert_eq!(bundle_sde_build(&bundle), Ok(2_500_000));
+        assert_eq!(loaded.sde_build, Some(2_500_000));
+        assert_eq!(loaded.types, data.types);
+        assert_eq!(loaded.type_dogma, data.type_dogma);
+        assert_eq!(loaded.dogma_attributes, data.dogma_attributes);
+        assert_eq!(loaded.dogma_effects, data.dogma_effects);
+        assert_eq!(loaded.alpha_skill_levels, data.alpha_skill_levels);
+        assert_eq!(loaded.alpha_skill_levels.get(&3300), Some(&4));
+        assert_eq!(loaded.localized_type_names, data.localized_type_names);
+        assert_eq!(loaded.type_id_to_name(587, "ja"), Some("リフター"));
+        assert_eq!(loaded.group_names, data.group_names);
+        assert_eq!(loaded.category_names, data.category_names);
+        assert_eq!(loaded.variation_parents, data.variation_parents);
+        assert_eq!(loaded.type_name_to_id("Rifter"), Some(587));
+    }
+
+    #[test]
+    fn optional_files_may_be_missing() {
+        let dir = test_data_dir("bundle-without-optional", false);
+        let loaded = Data::from_bundle(&pack_bundle(&dir.path, 1).unwrap()).unwrap();
+
+        assert_eq!(loaded.types.len(), 5);
+        assert!(loaded.alpha_skill_levels.is_empty());
+        assert!(loaded.localized_type_names.is_empty());
+        assert!(loaded.group_names.is_empty());
+    }
+
+    #[test]
+    fn unreadable_optional_file_is_an_error() {
+        let dir = test_data_dir("bundle-unreadable-optional", false);
+        std::fs::create_dir(dir.path.join("typeNames.json")).unwrap();
+
+        let error = pack_bundle(&dir.path, 1).unwrap_err();
+        assert!(error.contains("typeNames.json"), "{}", error);
+    }
+
+    #[test]
+    fn bad_magic_is_an_error() {
+        let dir = test_data_dir("bundle-bad-magic", false);
+        let mut bundle = pack_bundle(&dir.path, 1).unwrap();
+        bundle[0] = b'X';
+
+        assert_eq!(
+            Data::from_bundle(&bundle).err(),
+            Some("Not a data bundle".to_string())
+        );
+        assert!(Data::from_bundle(b"ESF").is_err());
+    }
+
+    #[test]
+    fn wrong_version_is_an_error() {
+        let dir = test_data_dir("bundle-wrong-version", false);
+        let mut bundle = pack_bundle(&dir.path, 1).unwrap();
+        bundle[8..12].copy_from_slice(&(BUNDLE_VERSION + 1).to_le_bytes());
+
+        assert_eq!(
+            Data::from_bundle(&bundle).err(),
+            Some(format!(
+                "Unsupported bundle version {}; expected {}",
+                BUNDLE_VERSION + 1,
+                BUNDLE_VERSION
+            ))
+        );
+    }
+
+    #[test]
+    fn truncated_section_is_an_error() {
+        let dir = test_data_dir("bundle-truncated", true);
+        let bundle = pack_bundle(&dir.path, 1).unwrap();
+
+        /* Cut anywhere after the header: the section that is cut short gives an error. */
+        for len in [20, 30, bundle.len() / 2, bundle.len() - 1] {
+            assert_eq!(
+                Data::from_bundle(&bundle[..len]).err(),
+                Some("Corrupt bundle: unexpected end of file".to_string()),
+                "bundle cut at {} bytes",
+                len
+            );
+        }
+    }
+}
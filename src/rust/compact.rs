@@ -0,0 +1,914 @@
+use std::{fs::File, ops::Deref, path::Path};
+
+use super::index::{closest_names, normalize};
+use super::Data;
+use crate::data_types;
+use crate::info::{Info, InfoName, InfoSkills};
+
+/* The compact format stores all data as tables of fixed-size records, sorted by ID
+ * (or by name for the name indices), so lookups are a binary search directly in the
+ * (memory-mapped) file; nothing is decoded up front. All values are 32-bit little
+ * endian. Strings are stored in a single blob, and referenced by (offset, length).
+ *
+ *   "ESFCMPCT"                         magic
+ *   u32                                format version
+ *   u32                                SDE build number (0 if unknown)
+ *   (u32 offset, u32 count) * TABLES   table descriptors; for the strings blob, the count is in bytes
+ */
+const COMPACT_MAGIC: &[u8; 8] = b"ESFCMPCT";
+const COMPACT_VERSION: u32 = 3;
+
+const TABLE_TYPES: usize = 0;
+const TABLE_TYPE_ATTRIBUTES: usize = 1;
+const TABLE_TYPE_EFFECTS: usize = 2;
+const TABLE_ATTRIBUTES: usize = 3;
+const TABLE_EFFECTS: usize = 4;
+const TABLE_MODIFIERS: usize = 5;
+const TABLE_TYPE_NAMES: usize = 6;
+const TABLE_NORMALIZED_TYPE_NAMES: usize = 7;
+const TABLE_ATTRIBUTE_NAMES: usize = 8;
+const TABLE_ALPHA_SKILLS: usize = 9;
+const TABLE_GROUP_NAMES: usize = 10;
+const TABLE_CATEGORY_NAMES: usize = 11;
+const TABLE_LOCALIZED_TYPE_NAMES: usize = 12;
+const TABLE_NORMALIZED_LOCALIZED_TYPE_NAMES: usize = 13;
+const TABLE_STRINGS: usize = 14;
+const TABLES: usize = 15;
+
+const HEADER_SIZE: usize = 16 + TABLES * 8;
+
+/* Record size (in 32-bit fields) per table; the strings blob is in bytes. */
+const RECORD_FIELDS: [usize; TABLES] = [18, 2, 2, 5, 14, 8, 4, 4, 4, 2, 3, 3, 4, 4, 0];
+
+/* Marker for optional values that are not set. */
+const NONE_I32: i32 = i32::MIN;
+
+/* Fields of the records; see write_compact() for the full layout. */
+const TYPE_FLAG_PUBLISHED: u32 = 1;
+const ATTRIBUTE_FLAG_PUBLISHED: u32 = 1;
+const ATTRIBUTE_FLAG_HIGH_IS_GOOD: u32 = 2;
+const ATTRIBUTE_FLAG_STACKABLE: u32 = 4;
+const EFFECT_FLAG_ELECTRONIC_CHANCE: u32 = 1;
+const EFFECT_FLAG_IS_ASSISTANCE: u32 = 2;
+const EFFECT_FLAG_IS_OFFENSIVE: u32 = 4;
+const EFFECT_FLAG_IS_WARP_SAFE: u32 = 8;
+const EFFECT_FLAG_PROPULSION_CHANCE: u32 = 16;
+const EFFECT_FLAG_RANGE_CHANCE: u32 = 32;
+
+fn optional_i32(value: Option<i32>) -> u32 {
+    value.unwrap_or(NONE_I32) as u32
+}
+
+fn optional_f32(value: Option<f32>) -> u32 {
+    value.unwrap_or(f32::NAN).to_bits()
+}
+
+fn flag(value: bool, flag: u32) -> u32 {
+    if value {
+        flag
+    } else {
+        0
+    }
+}
+
+struct CompactWriter {
+    tables: Vec<Vec<u32>>,
+    strings: Vec<u8>,
+}
+
+impl CompactWriter {
+    fn string(&mut self, value: &str) -> [u32; 2] {
+        let offset = self.strings.len() as u32;
+        self.strings.extend_from_slice(value.as_bytes());
+        [offset, value.len() as u32]
+    }
+
+    /* Name index, sorted by name; for equal names, published first and then the lowest ID. */
+    fn name_index(&mut self, table: usize, mut names: Vec<(String, i32, bool)>) {
+        names.sort_by(|a, b| a.0.cmp(&b.0).then(b.2.cmp(&a.2)).then(a.1.cmp(&b.1)));
+
+        for (name, id, published) in names {
+            let [offset, len] = self.string(&name);
+            self.tables[table].extend([offset, len, id as u32, published as u32]);
+        }
+    }
+}
+
+/* Convert loaded data into the compact format. */
+pub fn write_compact(data: &Data) -> Vec<u8> {
+    let mut writer = CompactWriter {
+        tables: vec![Vec::new(); TABLES],
+        strings: Vec::new(),
+    };
+
+    let mut type_ids: Vec<&i32> = data.types.keys().collect();
+    type_ids.sort();
+    for type_id in type_ids {
+        let type_ = &data.types[type_id];
+        let [name_offset, name_len] = writer.string(&type_.name);
+
+        let attribute_start = writer.tables[TABLE_TYPE_ATTRIBUTES].len() / 2;
+        let effect_start = writer.tables[TABLE_TYPE_EFFECTS].len() / 2;
+        if let Some(type_dogma) = data.type_dogma.get(type_id) {
+            for attribute in &type_dogma.dogma_attributes {
+                writer.tables[TABLE_TYPE_ATTRIBUTES]
+                    .extend([attribute.attribute_id as u32, attribute.value.to_bits()]);
+            }
+            for effect in &type_dogma.dogma_effects {
+                writer.tables[TABLE_TYPE_EFFECTS]
+                    .extend([effect.effect_id as u32, effect.is_default as u32]);
+            }
+        }
+        let attribute_count = writer.tables[TABLE_TYPE_ATTRIBUTES].len() / 2 - attribute_start;
+        let effect_count = writer.tables[TABLE_TYPE_EFFECTS].len() / 2 - effect_start;
+
+        writer.tables[TABLE_TYPES].extend([
+            *type_id as u32,
+            type_.group_id as u32,
+            type_.category_id as u32,
+            flag(type_.published, TYPE_FLAG_PUBLISHED),
+            optional_i32(type_.faction_id),
+            optional_i32(type_.market_group_id),
+            optional_i32(type_.meta_group_id),
+            optional_f32(type_.capacity),
+            optional_f32(type_.mass),
+            optional_f32(type_.radius),
+            optional_f32(type_.volume),
+            name_offset,
+            name_len,
+            attribute_start as u32,
+            attribute_count as u32,
+            effect_start as u32,
+            effect_count as u32,
+            optional_i32(data.variation_parents.get(type_id).copied()),
+        ]);
+    }
+
+    let mut attribute_ids: Vec<&i32> = data.dogma_attributes.keys().collect();
+    attribute_ids.sort();
+    for attribute_id in attribute_ids {
+        let attribute = &data.dogma_attributes[attribute_id];
+        let [name_offset, name_len] = writer.string(&attribute.name);
+
+        writer.tables[TABLE_ATTRIBUTES].extend([
+            *attribute_id as u32,
+            attribute.default_value.to_bits(),
+            flag(attribute.published, ATTRIBUTE_FLAG_PUBLISHED)
+                | flag(attribute.high_is_good, ATTRIBUTE_FLAG_HIGH_IS_GOOD)
+                | flag(attribute.stackable, ATTRIBUTE_FLAG_STACKABLE),
+            name_offset,
+            name_len,
+        ]);
+    }
+
+    let mut effect_ids: Vec<&i32> = data.dogma_effects.keys().collect();
+    effect_ids.sort();
+    for effect_id in effect_ids {
+        let effect = &data.dogma_effects[effect_id];
+        let [name_offset, name_len] = writer.string(&effect.name);
+
+        let modifier_start = writer.tables[TABLE_MODIFIERS].len() / 8;
+        for modifier in &effect.modifier_info {
+            writer.tables[TABLE_MODIFIERS].extend([
+                modifier.domain as u32,
+                modifier.func as u32,
+                optional_i32(modifier.modified_attribute_id),
+                optional_i32(modifier.modifying_attribute_id),
+                optional_i32(modifier.operation),
+                optional_i32(modifier.group_id),
+                optional_i32(modifier.skill_type_id),
+                optional_i32(modifier.effect_id),
+            ]);
+        }
+
+        writer.tables[TABLE_EFFECTS].extend([
+            *effect_id as u32,
+            effect.effect_category as u32,
+            flag(effect.electronic_chance, EFFECT_FLAG_ELECTRONIC_CHANCE)
+                | flag(effect.is_assistance, EFFECT_FLAG_IS_ASSISTANCE)
+                | flag(effect.is_offensive, EFFECT_FLAG_IS_OFFENSIVE)
+                | flag(effect.is_warp_safe, EFFECT_FLAG_IS_WARP_SAFE)
+                | flag(effect.propulsion_chance, EFFECT_FLAG_PROPULSION_CHANCE)
+                | flag(effect.range_chance, EFFECT_FLAG_RANGE_CHANCE),
+            optional_i32(effect.discharge_attribute_id),
+            optional_i32(effect.duration_attribute_id),
+            optional_i32(effect.range_attribute_id),
+            optional_i32(effect.falloff_attribute_id),
+            optional_i32(effect.tracking_speed_attribute_id),
+            optional_i32(effect.fitting_usage_chance_attribute_id),
+            optional_i32(effect.resistance_attribute_id),
+            name_offset,
+            name_len,
+            modifier_start as u32,
+            effect.modifier_info.len() as u32,
+        ]);
+    }
+
+    /* Type names, the same as the name lookups of Data: English and other languages apart. */
+    let mut type_names = Vec::new();
+    let mut localized_names = Vec::new();
+    for (type_id, type_) in &data.types {
+        type_names.push((type_.name.clone(), *type_id, type_.published));
+        for name in data
+            .localized_type_names
+            .get(type_id)
+            .into_iter()
+            .flat_map(|names| names.values())
+        {
+            localized_names.push((name.clone(), *type_id, type_.published));
+        }
+    }
+    for (table, normalized_table, mut names) in [
+        (TABLE_TYPE_NAMES, TABLE_NORMALIZED_TYPE_NAMES, type_names),
+        (
+            TABLE_LOCALIZED_TYPE_NAMES,
+            TABLE_NORMALIZED_LOCALIZED_TYPE_NAMES,
+            localized_names,
+        ),
+    ] {
+        names.sort();
+        names.dedup();
+        let normalized_names = names
+            .iter()
+            .map(|(name, type_id, published)| (normalize(name), *type_id, *published))
+            .collect();
+        writer.name_index(table, names);
+        writer.name_index(normalized_table, normalized_names);
+    }
+
+    let attribute_names = data
+        .dogma_attributes
+        .iter()
+        .map(|(attribute_id, attribute)| (attribute.name.clone(), *attribute_id, true))
+        .collect();
+    writer.name_index(TABLE_ATTRIBUTE_NAMES, attribute_names);
+
+    let mut alpha_skill_levels: Vec<(&i32, &i32)> = data.alpha_skill_levels.iter().collect();
+    alpha_skill_levels.sort();
+    for (type_id, level) in alpha_skill_levels {
+        writer.tables[TABLE_ALPHA_SKILLS].extend([*type_id as u32, *level as u32]);
+    }
+
+    for (table, names) in [
+        (TABLE_GROUP_NAMES, &data.group_names),
+        (TABLE_CATEGORY_NAMES, &data.category_names),
+    ] {
+        let mut names: Vec<(&i32, &String)> = names.iter().collect();
+        names.sort();
+        for (id, name) in names {
+            let [name_offset, name_len] = writer.string(name);
+            writer.tables[table].extend([*id as u32, name_offset, name_len]);
+        }
+    }
+
+    let mut compact = Vec::new();
+    compact.extend_from_slice(COMPACT_MAGIC);
+    compact.extend_from_slice(&COMPACT_VERSION.to_le_bytes());
+    compact.extend_from_slice(&data.sde_build.unwrap_or(0).to_le_bytes());
+
+    let mut offset = HEADER_SIZE;
+    for (table, fields) in writer.tables.iter().zip(RECORD_FIELDS) {
+        let (size, count) = match fields {
+            0 => (writer.strings.len(), writer.strings.len()),
+            _ => (table.len() * 4, table.len() / fields),
+        };
+        compact.extend_from_slice(&(offset as u32).to_le_bytes());
+        compact.extend_from_slice(&(count as u32).to_le_bytes());
+        offset += size;
+    }
+
+    for table in &writer.tables[..TABLE_STRINGS] {
+        for value in table {
+            compact.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+    compact.extend_from_slice(&writer.strings);
+
+    compact
+}
+
+pub enum CompactBuffer {
+    Mapped(memmap2::Mmap),
+    Owned(Vec<u8>),
+}
+
+impl Deref for CompactBuffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            CompactBuffer::Mapped(mmap) => mmap,
+            CompactBuffer::Owned(buf) => buf,
+        }
+    }
+}
+
+/* Data in the compact format; opening only reads the header, and checks the ranges
+ * records refer to, so lookups can index the tables without further checks.
+ *
+ * Type names in other languages are only stored for lookups; names are always written
+ * in English. Use InfoMain with a language for fits in another language. */
+pub struct CompactData {
+    buffer: CompactBuffer,
+    tables: [(usize, usize); TABLES],
+    pub sde_build: Option<u32>,
+}
+
+fn u32_at(buf: &[u8], position: usize) -> u32 {
+    u32::from_le_bytes(buf[position..position + 4].try_into().unwrap())
+}
+
+/* A single record of a table. */
+struct Record<'a> {
+    buf: &'a [u8],
+}
+
+impl Record<'_> {
+    fn u32(&self, field: usize) -> u32 {
+        u32_at(self.buf, field * 4)
+    }
+
+    fn i32(&self, field: usize) -> i32 {
+        self.u32(field) as i32
+    }
+
+    fn f32(&self, field: usize) -> f32 {
+        f32::from_bits(self.u32(field))
+    }
+
+    fn optional_i32(&self, field: usize) -> Option<i32> {
+        Some(self.i32(field)).filter(|value| *value != NONE_I32)
+    }
+
+    fn optional_f64(&self, field: usize) -> Option<f64> {
+        Some(self.f32(field))
+            .filter(|value| !value.is_nan())
+            .map(|value| value as f64)
+    }
+
+    fn flag(&self, field: usize, flag: u32) -> bool {
+        self.u32(field) & flag != 0
+    }
+}
+
+impl CompactData {
+    /* Memory-map a file in the compact format. The file should not be changed while it is in use. */
+    pub fn open(path: &Path) -> Result<CompactData, String> {
+        let file =
+            File::open(path).map_err(|e| format!("Cannot open {}: {}", path.display(), e))?;
+        /* Safety: the mapping is read-only; as with any memory-mapped file, it must not be
+         * truncated by another process while mapped. */
+        let mmap = unsafe { memmap2::Mmap::map(&file) }
+            .map_err(|e| format!("Cannot map {}: {}", path.display(), e))?;
+
+        CompactData::from_buffer(CompactBuffer::Mapped(mmap))
+            .map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn from_bytes(buf: Vec<u8>) -> Result<CompactData, String> {
+        CompactData::from_buffer(CompactBuffer::Owned(buf))
+    }
+
+    fn from_buffer(buffer: CompactBuffer) -> Result<CompactData, String> {
+        if buffer.len() < HEADER_SIZE || &buffer[..COMPACT_MAGIC.len()] != COMPACT_MAGIC {
+            return Err("Not a compact data file".to_string());
+        }
+        let version = u32_at(&buffer, 8);
+        if version != COMPACT_VERSION {
+            return Err(format!(
+                "Unsupported compact data version {}; expected {}",
+                version, COMPACT_VERSION
+            ));
+        }
+        let sde_build = Some(u32_at(&buffer, 12)).filter(|sde_build| *sde_build != 0);
+
+        let mut tables = [(0, 0); TABLES];
+        for (table, fields) in RECORD_FIELDS.iter().enumerate() {
+            let offset = u32_at(&buffer, 16 + table * 8) as usize;
+            let count = u32_at(&buffer, 20 + table * 8) as usize;
+
+            let size = match fields {
+                0 => Some(count),
+                _ => count.checked_mul(fields * 4),
+            };
+            match size.and_then(|size| offset.checked_add(size)) {
+                Some(end) if end <= buffer.len() => {}
+                _ => return Err("Corrupt compact data: table outside of the file".to_string()),
+            }
+
+            tables[table] = (offset, count);
+        }
+
+        let compact = CompactData {
+            buffer,
+            tables,
+            sde_build,
+        };
+        compact.check_ranges()?;
+
+        Ok(compact)
+    }
+
+    /* Check that the attributes and effects of every type, and the modifiers of every
+     * effect, are within their tables. */
+    fn check_ranges(&self) -> Result<(), String> {
+        /* (table, field with the start, table the range is in) */
+        let ranges = [
+            (TABLE_TYPES, 13, TABLE_TYPE_ATTRIBUTES),
+            (TABLE_TYPES, 15, TABLE_TYPE_EFFECTS),
+            (TABLE_EFFECTS, 12, TABLE_MODIFIERS),
+        ];
+
+        for (table, field, target) in ranges {
+            for index in 0..self.count(table) {
+                let record = self.record(table, index);
+                let start = record.u32(field) as usize;
+                match start.checked_add(record.u32(field + 1) as usize) {
+                    Some(end) if end <= self.count(target) => {}
+                    _ => {
+                        return Err(format!(
+                            "Corrupt compact data: record {} of table {} is outside of table {}",
+                            record.i32(0),
+                            table,
+                            target
+                        ))
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn count(&self, table: usize) -> usize {
+        self.tables[table].1
+    }
+
+    fn record(&self, table: usize, index: usize) -> Record<'_> {
+        let (offset, _) = self.tables[table];
+        let size = RECORD_FIELDS[table] * 4;
+        let start = index
+            .checked_mul(size)
+            .and_then(|position| position.checked_add(offset))
+            .expect("record outside of its table");
+        Record {
+            buf: &self.buffer[start..start + size],
+        }
+    }
+
+    /* Binary search a table sorted by ID (the first field). */
+    fn find(&self, table: usize, id: i32) -> Option<Record<'_>> {
+        let (mut low, mut high) = (0, self.count(table));
+        while low < high {
+            let middle = (low + high) / 2;
+            let record = self.record(table, middle);
+            match record.i32(0).cmp(&id) {
+                std::cmp::Ordering::Less => low = middle + 1,
+                std::cmp::Ordering::Greater => high = middle,
+                std::cmp::Ordering::Equal => return Some(record),
+            }
+        }
+
+        None
+    }
+
+    fn string(&self, offset: u32, len: u32) -> &str {
+        let (strings_offset, strings_len) = self.tables[TABLE_STRINGS];
+        let (offset, len) = (offset as usize, len as usize);
+        if !matches!(offset.checked_add(len), Some(end) if end <= strings_len) {
+            return "";
+        }
+
+        let start = strings_offset + offset;
+        std::str::from_utf8(&self.buffer[start..start + len]).unwrap_or("")
+    }
+
+    /* Binary search a name index; the first match is the preferred one. */
+    fn find_name(&self, table: usize, name: &str) -> Option<i32> {
+        let name_of = |index: usize| {
+            let record = self.record(table, index);
+            self.string(record.u32(0), record.u32(1))
+        };
+
+        let (mut low, mut high) = (0, self.count(table));
+        while low < high {
+            let middle = (low + high) / 2;
+            if name_of(middle) < name {
+                low = middle + 1;
+            } else {
+                high = middle;
+            }
+        }
+
+        (low < self.count(table) && name_of(low) == name).then(|| self.record(table, low).i32(2))
+    }
+
+    pub fn type_name_to_id(&self, name: &str) -> Option<i32> {
+        let normalized = normalize(name);
+
+        /* English first; other languages only when that misses. */
+        self.find_name(TABLE_TYPE_NAMES, name)
+            .or_else(|| self.find_name(TABLE_NORMALIZED_TYPE_NAMES, &normalized))
+            .or_else(|| self.find_name(TABLE_LOCALIZED_TYPE_NAMES, name))
+            .or_else(|| self.find_name(TABLE_NORMALIZED_LOCALIZED_TYPE_NAMES, &normalized))
+    }
+
+    pub fn attribute_name_to_id(&self, name: &str) -> Option<i32> {
+        self.find_name(TABLE_ATTRIBUTE_NAMES, name)
+    }
+}
+
+impl Info for CompactData {
+    fn get_dogma_attributes(&self, type_id: i32) -> Vec<data_types::TypeDogmaAttribute> {
+        let Some(type_) = self.find(TABLE_TYPES, type_id) else {
+            return vec![];
+        };
+
+        let start = type_.u32(13) as usize;
+        (start..start + type_.u32(14) as usize)
+            .map(|index| {
+                let attribute = self.record(TABLE_TYPE_ATTRIBUTES, index);
+                data_types::TypeDogmaAttribute {
+                    attributeID: attribute.i32(0),
+                    value: attribute.f32(1) as f64,
+                }
+            })
+            .collect()
+    }
+
+    fn get_dogma_attribute(&self, attribute_id: i32) -> data_types::DogmaAttribute {
+        match self.find(TABLE_ATTRIBUTES, attribute_id) {
+            None => data_types::DogmaAttribute {
+                defaultValue: 0.0,
+                highIsGood: false,
+                stackable: false,
+            },
+            Some(attribute) => data_types::DogmaAttribute {
+                defaultValue: attribute.f32(1) as f64,
+                highIsGood: attribute.flag(2, ATTRIBUTE_FLAG_HIGH_IS_GOOD),
+                stackable: attribute.flag(2, ATTRIBUTE_FLAG_STACKABLE),
+            },
+        }
+    }
+
+    fn get_dogma_effects(&self, type_id: i32) -> Vec<data_types::TypeDogmaEffect> {
+        let Some(type_) = self.find(TABLE_TYPES, type_id) else {
+            return vec![];
+        };
+
+        let start = type_.u32(15) as usize;
+        (start..start + type_.u32(16) as usize)
+            .map(|index| {
+                let effect = self.record(TABLE_TYPE_EFFECTS, index);
+                data_types::TypeDogmaEffect {
+                    effectID: effect.i32(0),
+                    isDefault: effect.u32(1) != 0,
+                }
+            })
+            .collect()
+    }
+
+    fn get_dogma_effect(&self, effect_id: i32) -> data_types::DogmaEffect {
+        let Some(effect) = self.find(TABLE_EFFECTS, effect_id) else {
+            return data_types::DogmaEffect {
+                dischargeAttributeID: None,
+                durationAttributeID: None,
+                effectCategory: 0,
+                electronicChance: false,
+                isAssistance: false,
+                isOffensive: false,
+                isWarpSafe: false,
+                propulsionChance: false,
+                rangeChance: false,
+                rangeAttributeID: None,
+                falloffAttributeID: None,
+                trackingSpeedAttributeID: None,
+                fittingUsageChanceAttributeID: None,
+                resistanceAttributeID: None,
+                modifierInfo: vec![],
+            };
+        };
+
+        let start = effect.u32(12) as usize;
+        let modifier_info = (start..start + effect.u32(13) as usize)
+            .map(|index| {
+                let modifier = self.record(TABLE_MODIFIERS, index);
+                data_types::DogmaEffectModifierInfo {
+                    domain: modifier.i32(0).into(),
+                    func: modifier.i32(1).into(),
+                    modifiedAttributeID: modifier.optional_i32(2),
+                    modifyingAttributeID: modifier.optional_i32(3),
+                    operation: modifier.optional_i32(4),
+                    groupID: modifier.optional_i32(5),
+                    skillTypeID: modifier.optional_i32(6),
+                    effectID: modifier.optional_i32(7),
+                }
+            })
+            .collect();
+
+        data_types::DogmaEffect {
+            dischargeAttributeID: effect.optional_i32(3),
+            durationAttributeID: effect.optional_i32(4),
+            effectCategory: effect.i32(1),
+            electronicChance: effect.flag(2, EFFECT_FLAG_ELECTRONIC_CHANCE),
+            isAssistance: effect.flag(2, EFFECT_FLAG_IS_ASSISTANCE),
+            isOffensive: effect.flag(2, EFFECT_FLAG_IS_OFFENSIVE),
+            isWarpSafe: effect.flag(2, EFFECT_FLAG_IS_WARP_SAFE),
+            propulsionChance: effect.flag(2, EFFECT_FLAG_PROPULSION_CHANCE),
+            rangeChance: effect.flag(2, EFFECT_FLAG_RANGE_CHANCE),
+            rangeAttributeID: effect.optional_i32(5),
+            falloffAttributeID: effect.optional_i32(6),
+            trackingSpeedAttributeID: effect.optional_i32(7),
+            fittingUsageChanceAttributeID: effect.optional_i32(8),
+            resistanceAttributeID: effect.optional_i32(9),
+            modifierInfo: modifier_info,
+        }
+    }
+
+    fn get_type(&self, type_id: i32) -> data_types::Type {
+        match self.find(TABLE_TYPES, type_id) {
+            None => data_types::Type {
+                groupID: 0,
+                categoryID: 0,
+                published: false,
+                marketGroupID: None,
+                metaGroupID: None,
+                variationParentTypeID: None,
+                capacity: None,
+                mass: None,
+                volume: None,
+                radius: None,
+            },
+            Some(type_) => data_types::Type {
+                groupID: type_.i32(1),
+                categoryID: type_.i32(2),
+                published: type_.flag(3, TYPE_FLAG_PUBLISHED),
+                marketGroupID: type_.optional_i32(5),
+                metaGroupID: type_.optional_i32(6),
+                variationParentTypeID: type_.optional_i32(17),
+                capacity: type_.optional_f64(7),
+                mass: type_.optional_f64(8),
+                radius: type_.optional_f64(9),
+                volume: type_.optional_f64(10),
+            },
+        }
+    }
+
+    fn attribute_name_to_id(&self, name: &str) -> i32 {
+        CompactData::attribute_name_to_id(self, name).unwrap_or(0)
+    }
+
+    fn attribute_id_to_name(&self, attribute_id: i32) -> Option<String> {
+        let attribute = self.find(TABLE_ATTRIBUTES, attribute_id)?;
+        Some(self.string(attribute.u32(3), attribute.u32(4)).to_string())
+    }
+
+    fn get_group_name(&self, group_id: i32) -> Option<String> {
+        let group = self.find(TABLE_GROUP_NAMES, group_id)?;
+        Some(self.string(group.u32(1), group.u32(2)).to_string())
+    }
+
+    fn get_category_name(&self, category_id: i32) -> Option<String> {
+        let category = self.find(TABLE_CATEGORY_NAMES, category_id)?;
+        Some(self.string(category.u32(1), category.u32(2)).to_string())
+    }
+}
+
+impl InfoName for CompactData {
+    fn type_name_to_id(&self, name: &str) -> i32 {
+        CompactData::type_name_to_id(self, name).unwrap_or(0)
+    }
+
+    /* Always the English name; see CompactData. */
+    fn type_id_to_name(&self, type_id: i32) -> Option<String> {
+        let type_ = self.find(TABLE_TYPES, type_id)?;
+        Some(self.string(type_.u32(11), type_.u32(12)).to_string())
+    }
+
+    fn type_name_suggestions(&self, name: &str) -> Vec<String> {
+        let published_names = (0..self.count(TABLE_TYPE_NAMES))
+            .map(|index| self.record(TABLE_TYPE_NAMES, index))
+            .filter(|record| record.u32(3) != 0)
+            .map(|record| self.string(record.u32(0), record.u32(1)));
+
+        closest_names(name, published_names, 3)
+    }
+}
+
+impl InfoSkills for CompactData {
+    fn skill_type_ids(&self) -> Vec<i32> {
+        (0..self.count(TABLE_TYPES))
+            .map(|index| self.record(TABLE_TYPES, index))
+            .filter(|type_| type_.i32(2) == 16 && type_.flag(3, TYPE_FLAG_PUBLISHED)) // Skill
+            .map(|type_| type_.i32(0))
+            .collect()
+    }
+
+    fn alpha_skill_level(&self, type_id: i32) -> i32 {
+        self.find(TABLE_ALPHA_SKILLS, type_id)
+            .map(|skill| skill.i32(1))
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_data::test_data_dir;
+    use super::super::InfoMain;
+    use super::*;
+
+    /* Type, attribute and effect IDs of the test data, plus one that doesn't exist. */
+    const TYPE_IDS: [i32; 6] = [587, 588, 484, 185, 3300, 1];
+    const ATTRIBUTE_IDS: [i32; 3] = [9, 128, 1];
+    const EFFECT_IDS: [i32; 3] = [12, 34, 1];
+
+    fn compact_of(name: &str) -> (Data, Vec<u8>) {
+        let dir = test_data_dir(name, true);
+        let mut data = Data::new(&dir.path).unwrap();
+        data.sde_build = Some(2_500_000);
+        let compact = write_compact(&data);
+        (data, compact)
+    }
+
+    #[test]
+    fn write_and_open_keeps_the_data() {
+        let (data, compact) = compact_of("compact-round-trip");
+        let info = InfoMain::new(&data);
+        let compact = CompactData::from_bytes(compact).unwrap();
+
+        assert_eq!(compact.sde_build, Some(2_500_000));
+
+        /* The types only have part of their optional values, so this also covers that
+         * missing values don't come back as zero. */
+        for type_id in TYPE_IDS {
+            assert_eq!(
+                format!("{:?}", Info::get_type(&compact, type_id)),
+                format!("{:?}", info.get_type(type_id)),
+                "type {}",
+                type_id
+            );
+            assert_eq!(
+                format!("{:?}", compact.get_dogma_attributes(type_id)),
+                format!("{:?}", info.get_dogma_attributes(type_id))
+            );
+            assert_eq!(
+                format!("{:?}", compact.get_dogma_effects(type_id)),
+                format!("{:?}", info.get_dogma_effects(type_id))
+            );
+            assert_eq!(
+                InfoName::type_id_to_name(&compact, type_id),
+                info.type_id_to_name(type_id)
+            );
+            assert_eq!(
+                compact.alpha_skill_level(type_id),
+                info.alpha_skill_level(type_id)
+            );
+        }
+        for attribute_id in ATTRIBUTE_IDS {
+            assert_eq!(
+                format!("{:?}", compact.get_dogma_attribute(attribute_id)),
+                format!("{:?}", info.get_dogma_attribute(attribute_id))
+            );
+            assert_eq!(
+                compact.attribute_id_to_name(attribute_id),
+                info.attribute_id_to_name(attribute_id)
+            );
+        }
+        for effect_id in EFFECT_IDS {
+            assert_eq!(
+                format!("{:?}", compact.get_dogma_effect(effect_id)),
+                format!("{:?}", info.get_dogma_effect(effect_id)),
+                "effect {}",
+                effect_id
+            );
+        }
+
+        assert_eq!(compact.alpha_skill_level(3300), 4);
+        assert_eq!(compact.skill_type_ids(), info.skill_type_ids());
+        assert_eq!(compact.get_group_name(25), Some("Frigate".to_string()));
+        assert_eq!(compact.get_category_name(6), Some("Ship".to_string()));
+        assert_eq!(compact.get_group_name(26), None);
+    }
+
+    #[test]
+    fn name_lookups_match_data() {
+        let (data, compact) = compact_of("compact-names");
+        let info = InfoMain::new(&data);
+        let compact = CompactData::from_bytes(compact).unwrap();
+
+        for name in [
+            "Rifter",
+            "  rifter ",
+            "125mm Gatling AutoCannon I",
+            "125MM GATLING AUTOCANNON I",
+            "EMP S",
+            "Gunnery",
+            "リフター",
+            "Rifer",
+            "",
+        ] {
+            assert_eq!(
+                InfoName::type_name_to_id(&compact, name),
+                info.type_name_to_id(name),
+                "name '{}'",
+                name
+            );
+            assert_eq!(
+                compact.type_name_suggestions(name),
+                info.type_name_suggestions(name)
+            );
+        }
+        /* The published type wins from the unpublished one with the same name. */
+        assert_eq!(CompactData::type_name_to_id(&compact, "Rifter"), Some(587));
+        assert_eq!(
+            CompactData::type_name_to_id(&compact, "リフター"),
+            Some(587)
+        );
+        assert_eq!(CompactData::type_name_to_id(&compact, "Unknown"), None);
+
+        assert_eq!(CompactData::attribute_name_to_id(&compact, "hp"), Some(9));
+        assert_eq!(
+            CompactData::attribute_name_to_id(&compact, "chargeSize"),
+            Some(128)
+        );
+        assert_eq!(CompactData::attribute_name_to_id(&compact, "unknown"), None);
+    }
+
+    #[test]
+    fn truncated_data_is_an_error() {
+        let (_, compact) = compact_of("compact-truncated");
+
+        for len in 0..compact.len() {
+            assert!(
+                CompactData::from_bytes(compact[..len].to_vec()).is_err(),
+                "compact data cut at {} bytes",
+                len
+            );
+        }
+    }
+
+    #[test]
+    fn corrupt_header_is_an_error() {
+        let (_, compact) = compact_of("compact-corrupt-header");
+
+        let mut corrupt = compact.clone();
+        corrupt[0] = b'X';
+        assert_eq!(
+            CompactData::from_bytes(corrupt).err(),
+            Some("Not a compact data file".to_string())
+        );
+
+        let mut corrupt = compact.clone();
+        corrupt[8..12].copy_from_slice(&(COMPACT_VERSION + 1).to_le_bytes());
+        assert!(CompactData::from_bytes(corrupt).is_err());
+
+        /* A table count that doesn't fit in the file. */
+        let mut corrupt = compact.clone();
+        let count = 20 + TABLE_MODIFIERS * 8;
+        corrupt[count..count + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert_eq!(
+            CompactData::from_bytes(corrupt).err(),
+            Some("Corrupt compact data: table outside of the file".to_string())
+        );
+    }
+
+    #[test]
+    fn corrupt_record_range_is_an_error() {
+        let (_, compact) = compact_of("compact-corrupt-range");
+        let types_offset = u32_at(&compact, 16) as usize;
+
+        /* The attribute count of the first type. */
+        let mut corrupt = compact.clone();
+        let position = types_offset + 14 * 4;
+        corrupt[position..position + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(CompactData::from_bytes(corrupt)
+            .is_err_and(|e| e.starts_with("Corrupt compact data: record 185 of table 0")));
+    }
+
+    #[test]
+    fn corrupt_bytes_do_not_panic() {
+        let (_, compact) = compact_of("compact-corrupt-bytes");
+
+        /* Whatever byte is damaged, opening either fails, or gives data that can be used. */
+        for position in 0..compact.len() {
+            let mut corrupt = compact.clone();
+            corrupt[position] ^= 0xff;
+
+            if let Ok(compact) = CompactData::from_bytes(corrupt) {
+                for type_id in TYPE_IDS {
+                    compact.get_type(type_id);
+                    compact.get_dogma_attributes(type_id);
+                    compact.get_dogma_effects(type_id);
+                    InfoName::type_id_to_name(&compact, type_id);
+                    compact.alpha_skill_level(type_id);
+                }
+                for effect_id in EFFECT_IDS {
+                    compact.get_dogma_effect(effect_id);
+                }
+                InfoName::type_name_to_id(&compact, "Rifter");
+                compact.type_name_suggestions("Rifer");
+                compact.skill_type_ids();
+            }
+        }
+    }
+}
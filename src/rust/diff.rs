@@ -0,0 +1,218 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+
+use super::batch::calculate_job;
+use super::{Data, InfoMain};
+use crate::calculate::Input;
+use crate::data_types::EsfFit;
+
+/* Changes smaller than this (relative) are rounding noise, not balance changes. */
+const EPSILON: f64 = 1e-9;
+
+#[derive(Serialize, Debug)]
+pub struct ValueChange {
+    pub attribute_id: i32,
+    pub name: String,
+    pub old: Option<f64>,
+    pub new: Option<f64>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct TypeChange {
+    pub type_id: i32,
+    pub name: String,
+    pub attributes: Vec<ValueChange>,
+    pub effects_added: Vec<i32>,
+    pub effects_removed: Vec<i32>,
+    /* Effects the type has in both, but whose definition (like modifiers) changed. */
+    pub effects_changed: Vec<i32>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct FitChange {
+    pub name: String,
+    /* Calculated attributes of the hull that changed; or why the fit could not be calculated. */
+    pub stats: Result<Vec<ValueChange>, String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct DataDiff {
+    pub types: Vec<TypeChange>,
+    pub fits: Vec<FitChange>,
+}
+
+fn changed(old: Option<f64>, new: Option<f64>) -> bool {
+    match (old, new) {
+        (Some(old), Some(new)) => (old - new).abs() > EPSILON * old.abs().max(new.abs()),
+        (None, None) => false,
+        _ => true,
+    }
+}
+
+fn attribute_name(old: &Data, new: &Data, attribute_id: i32) -> String {
+    new.dogma_attributes
+        .get(&attribute_id)
+        .or_else(|| old.dogma_attributes.get(&attribute_id))
+        .map(|attribute| attribute.name.clone())
+        .unwrap_or_default()
+}
+
+fn type_attributes(data: &Data, type_id: i32) -> BTreeMap<i32, f64> {
+    data.type_dogma
+        .get(&type_id)
+        .map(|type_dogma| {
+            type_dogma
+                .dogma_attributes
+                .iter()
+                .map(|attribute| (attribute.attribute_id, attribute.value as f64))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn type_effects(data: &Data, type_id: i32) -> BTreeSet<i32> {
+    data.type_dogma
+        .get(&type_id)
+        .map(|type_dogma| {
+            type_dogma
+                .dogma_effects
+                .iter()
+                .map(|effect| effect.effect_id)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn value_changes(
+    old: &Data,
+    new: &Data,
+    old_values: &BTreeMap<i32, f64>,
+    new_values: &BTreeMap<i32, f64>,
+) -> Vec<ValueChange> {
+    let attribute_ids: BTreeSet<&i32> = old_values.keys().chain(new_values.keys()).collect();
+
+    attribute_ids
+        .into_iter()
+        .filter_map(|attribute_id| {
+            let old_value = old_values.get(attribute_id).copied();
+            let new_value = new_values.get(attribute_id).copied();
+            if !changed(old_value, new_value) {
+                return None;
+            }
+
+            Some(ValueChange {
+                attribute_id: *attribute_id,
+                name: attribute_name(old, new, *attribute_id),
+                old: old_value,
+                new: new_value,
+            })
+        })
+        .collect()
+}
+
+/* All types used by a fit: hull, modules, charges, drones, implants, boosters and mode. */
+pub fn fit_type_ids(fit: &EsfFit) -> BTreeSet<i32> {
+    let mut type_ids = BTreeSet::from([fit.ship_type_id]);
+
+    for module in &fit.modules {
+        type_ids.insert(module.type_id);
+        if let Some(charge) = &module.charge {
+            type_ids.insert(charge.type_id);
+        }
+    }
+    type_ids.extend(fit.drones.iter().map(|drone| drone.type_id));
+    type_ids.extend(fit.implants.iter().map(|implant| implant.type_id));
+    type_ids.extend(fit.boosters.iter().map(|booster| booster.type_id));
+    type_ids.extend(fit.mode.iter().map(|mode| mode.type_id));
+
+    type_ids
+}
+
+/* Per type, which attributes and effects differ between two data sets. Types without changes are left out. */
+pub fn diff_types(old: &Data, new: &Data, type_ids: &BTreeSet<i32>) -> Vec<TypeChange> {
+    type_ids
+        .iter()
+        .filter_map(|type_id| {
+            let attributes = value_changes(
+                old,
+                new,
+                &type_attributes(old, *type_id),
+                &type_attributes(new, *type_id),
+            );
+
+            let old_effects = type_effects(old, *type_id);
+            let new_effects = type_effects(new, *type_id);
+            let effects_changed: Vec<i32> = old_effects
+                .intersection(&new_effects)
+                .filter(|effect_id| {
+                    old.dogma_effects.get(effect_id) != new.dogma_effects.get(effect_id)
+                })
+                .copied()
+                .collect();
+
+            let type_change = TypeChange {
+                type_id: *type_id,
+                name: new
+                    .types
+                    .get(type_id)
+                    .or_else(|| old.types.get(type_id))
+                    .map(|type_| type_.name.clone())
+                    .unwrap_or_default(),
+                attributes,
+                effects_added: new_effects.difference(&old_effects).copied().collect(),
+                effects_removed: old_effects.difference(&new_effects).copied().collect(),
+                effects_changed,
+            };
+
+            let unchanged = type_change.attributes.is_empty()
+                && type_change.effects_added.is_empty()
+                && type_change.effects_removed.is_empty()
+                && type_change.effects_changed.is_empty();
+            (!unchanged).then_some(type_change)
+        })
+        .collect()
+}
+
+fn hull_values(data: &Data, input: &Input) -> Result<BTreeMap<i32, f64>, String> {
+    let ship = calculate_job(&InfoMain::new(data), input)?;
+    Ok(ship
+        .hull
+        .attributes
+        .iter()
+        .map(|(attribute_id, attribute)| {
+            (
+                *attribute_id,
+                attribute.value.unwrap_or(attribute.base_value),
+            )
+        })
+        .collect())
+}
+
+/* Calculate a fit against both data sets, and list the attributes of the hull that changed. */
+pub fn diff_fit(old: &Data, new: &Data, input: &Input) -> Result<Vec<ValueChange>, String> {
+    let old_values = hull_values(old, input).map_err(|e| format!("Old data: {}", e))?;
+    let new_values = hull_values(new, input).map_err(|e| format!("New data: {}", e))?;
+
+    Ok(value_changes(old, new, &old_values, &new_values))
+}
+
+/* Compare two data sets for a list of (named) fits: what changed in the types they
+ * use, and how that changes their calculated statistics. Skills are not compared. */
+pub fn diff_data(old: &Data, new: &Data, fits: &[(String, Input)]) -> DataDiff {
+    let type_ids = fits
+        .iter()
+        .flat_map(|(_, input)| fit_type_ids(&input.fit))
+        .collect();
+
+    DataDiff {
+        types: diff_types(old, new, &type_ids),
+        fits: fits
+            .iter()
+            .map(|(name, input)| FitChange {
+                name: name.clone(),
+                stats: diff_fit(old, new, input),
+            })
+            .collect(),
+    }
+}
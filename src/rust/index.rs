@@ -0,0 +1,186 @@
+use std::collections::HashMap;
+
+/* Lookup from a name to its ID, both exact and normalized (ignoring case and whitespace).
+ * When several entries share a name, the lowest ID wins; this keeps
+ * lookups deterministic, as the SDE contains duplicate names. */
+#[derive(Default)]
+pub struct NameIndex {
+    exact: HashMap<String, i32>,
+    normalized: HashMap<String, i32>,
+}
+
+/* Lowercase, with every run of whitespace collapsed into a single space. */
+pub(super) fn normalize(name: &str) -> String {
+    name.split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/* Levenshtein distance between two strings, in characters. */
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, ca) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+/* Candidates closest to the given name by edit distance, best first. Only names that are
+ * reasonably close (a quarter of the length, with a minimum of 2 edits) are returned. */
+pub(super) fn closest_names<'a>(
+    name: &str,
+    candidates: impl Iterator<Item = &'a str>,
+    limit: usize,
+) -> Vec<String> {
+    let name: Vec<char> = normalize(name).chars().collect();
+    let max_distance = (name.len() / 4).max(2);
+
+    let mut suggestions: Vec<(usize, &str)> = candidates
+        .filter_map(|candidate| {
+            let normalized: Vec<char> = normalize(candidate).chars().collect();
+            if normalized.len().abs_diff(name.len()) > max_distance {
+                return None;
+            }
+
+            let distance = edit_distance(&name, &normalized);
+            (distance <= max_distance).then_some((distance, candidate))
+        })
+        .collect();
+    suggestions.sort();
+    suggestions.dedup();
+
+    suggestions
+        .into_iter()
+        .take(limit)
+        .map(|(_, candidate)| candidate.to_string())
+        .collect()
+}
+
+impl NameIndex {
+    pub fn insert(&mut self, name: &str, id: i32) {
+        let exact = self.exact.entry(name.to_string()).or_insert(id);
+        *exact = (*exact).min(id);
+
+        let normalized = self.normalized.entry(normalize(name)).or_insert(id);
+        *normalized = (*normalized).min(id);
+    }
+
+    pub fn get(&self, name: &str) -> Option<i32> {
+        self.exact.get(name).copied()
+    }
+
+    pub fn get_normalized(&self, name: &str) -> Option<i32> {
+        self.normalized.get(&normalize(name)).copied()
+    }
+
+    /* Names closest to the given name by edit distance, best first. */
+    pub fn suggestions(&self, name: &str, limit: usize) -> Vec<String> {
+        closest_names(
+            name,
+            self.exact.keys().map(|candidate| candidate.as_str()),
+            limit,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index() -> NameIndex {
+        let mut index = NameIndex::default();
+        index.insert("Damage Control II", 2048);
+        index.insert("Medium Shield Extender I", 3831);
+        index.insert("125mm Gatling AutoCannon I", 484);
+        index.insert("Rifter", 587);
+        index.insert("Rifter", 1);
+        index
+    }
+
+    #[test]
+    fn normalized_lookup_ignores_case_and_whitespace() {
+        let index = index();
+
+        assert_eq!(index.get("damage control ii"), None);
+        assert_eq!(index.get_normalized("damage control ii"), Some(2048));
+        assert_eq!(index.get_normalized("  Damage   Control\tII "), Some(2048));
+        assert_eq!(index.get_normalized("DAMAGE CONTROL II"), Some(2048));
+    }
+
+    #[test]
+    fn normalized_lookup_keeps_punctuation() {
+        let index = index();
+
+        assert_eq!(
+            index.get_normalized("125mm gatling autocannon i"),
+            Some(484)
+        );
+        assert_eq!(index.get_normalized("125mm Gatling Auto-Cannon I"), None);
+        assert_eq!(
+            index.suggestions("125mm Gatling Auto-Cannon I", 3),
+            vec!["125mm Gatling AutoCannon I"]
+        );
+    }
+
+    #[test]
+    fn duplicate_names_use_the_lowest_id() {
+        let index = index();
+
+        assert_eq!(index.get("Rifter"), Some(1));
+        assert_eq!(index.get_normalized("rifter"), Some(1));
+    }
+
+    #[test]
+    fn near_misses_are_suggested() {
+        let index = index();
+
+        assert_eq!(
+            index.suggestions("Damage Contrl II", 3),
+            vec!["Damage Control II"]
+        );
+        assert_eq!(
+            index.suggestions("medium shield extendor i", 3),
+            vec!["Medium Shield Extender I"]
+        );
+        /* Short names still allow two edits. */
+        assert_eq!(index.suggestions("Rftr", 3), vec!["Rifter"]);
+    }
+
+    #[test]
+    fn distant_names_are_not_suggested() {
+        let index = index();
+
+        assert!(index.suggestions("Large Armor Repairer I", 3).is_empty());
+        assert!(index.suggestions("Raven", 3).is_empty());
+        /* A quarter of the length: 17 characters allow 4 edits, not 5. */
+        assert_eq!(
+            index.suggestions("Dbmbgb Cbntrol II", 3),
+            vec!["Damage Control II"]
+        );
+        assert!(index.suggestions("Dbmbgb Cbntrbl II", 3).is_empty());
+    }
+
+    #[test]
+    fn edit_distance_counts_characters() {
+        let distance = |a: &str, b: &str| {
+            edit_distance(
+                &a.chars().collect::<Vec<_>>(),
+                &b.chars().collect::<Vec<_>>(),
+            )
+        };
+
+        assert_eq!(distance("", ""), 0);
+        assert_eq!(distance("kitten", "sitting"), 3);
+        assert_eq!(distance("Rifter", "Rifter"), 0);
+        assert_eq!(distance("", "abc"), 3);
+    }
+}
@@ -1,17 +1,11 @@
-use std::collections::BTreeMap;
-
 use super::Data;
 use crate::data_types;
-use crate::info::{Info, InfoName};
+use crate::info::{Info, InfoName, InfoSkills};
 
 pub struct InfoMain<'a> {
-    pub fit: data_types::EsfFit,
-    pub skills: BTreeMap<i32, i32>,
-    pub data: &'a Data,
-}
-
-pub struct InfoNameMain<'a> {
     pub data: &'a Data,
+    /* Language type names are written in; names in any language are read. */
+    pub language: String,
 }
 
 impl Info for InfoMain<'_> {
@@ -90,6 +84,7 @@ impl Info for InfoMain<'_> {
                         func: modifier.func.into(),
                         groupID: modifier.group_id,
                         skillTypeID: modifier.skill_type_id,
+                        effectID: modifier.effect_id,
                         modifiedAttributeID: modifier.modified_attribute_id,
                         modifyingAttributeID: modifier.modifying_attribute_id,
                     });
@@ -121,6 +116,10 @@ impl Info for InfoMain<'_> {
             None => data_types::Type {
                 groupID: 0,
                 categoryID: 0,
+                published: false,
+                marketGroupID: None,
+                metaGroupID: None,
+                variationParentTypeID: None,
                 capacity: None,
                 mass: None,
                 volume: None,
@@ -129,6 +128,10 @@ impl Info for InfoMain<'_> {
             Some(type_) => data_types::Type {
                 groupID: type_.group_id,
                 categoryID: type_.category_id,
+                published: type_.published,
+                marketGroupID: type_.market_group_id,
+                metaGroupID: type_.meta_group_id,
+                variationParentTypeID: self.data.variation_parents.get(&type_id).copied(),
                 capacity: type_.capacity.map(|x| x as f64),
                 mass: type_.mass.map(|x| x as f64),
                 volume: type_.volume.map(|x| x as f64),
@@ -138,79 +141,72 @@ impl Info for InfoMain<'_> {
     }
 
     fn attribute_name_to_id(&self, name: &str) -> i32 {
-        for (attribute_id, attribute) in &self.data.dogma_attributes {
-            if attribute.name == name {
-                return *attribute_id;
-            }
-        }
-        0
+        self.data.attribute_name_to_id(name).unwrap_or(0)
     }
 
-    fn skills(&self) -> &BTreeMap<i32, i32> {
-        &self.skills
+    fn attribute_id_to_name(&self, attribute_id: i32) -> Option<String> {
+        self.data
+            .dogma_attributes
+            .get(&attribute_id)
+            .map(|attribute| attribute.name.clone())
     }
 
-    fn fit(&self) -> &data_types::EsfFit {
-        &self.fit
+    fn get_group_name(&self, group_id: i32) -> Option<String> {
+        self.data.group_names.get(&group_id).cloned()
+    }
+
+    fn get_category_name(&self, category_id: i32) -> Option<String> {
+        self.data.category_names.get(&category_id).cloned()
     }
 }
 
-impl InfoName for InfoNameMain<'_> {
-    fn get_dogma_effects(&self, type_id: i32) -> Vec<data_types::TypeDogmaEffect> {
-        match self.data.type_dogma.get(&type_id) {
-            None => vec![],
-            Some(type_dogma) => {
-                let mut effects = vec![];
-                for effect in &type_dogma.dogma_effects {
-                    effects.push(data_types::TypeDogmaEffect {
-                        effectID: effect.effect_id,
-                        isDefault: effect.is_default,
-                    });
-                }
-                effects
-            }
-        }
+impl InfoName for InfoMain<'_> {
+    fn type_name_to_id(&self, name: &str) -> i32 {
+        self.data
+            .type_name_to_id(name)
+            .or_else(|| self.data.type_name_to_id_normalized(name))
+            .or_else(|| self.data.localized_type_name_to_id(name))
+            .unwrap_or(0)
     }
 
-    fn get_type(&self, type_id: i32) -> data_types::Type {
-        match self.data.types.get(&type_id) {
-            None => data_types::Type {
-                groupID: 0,
-                categoryID: 0,
-                capacity: None,
-                mass: None,
-                volume: None,
-                radius: None,
-            },
-            Some(type_) => data_types::Type {
-                groupID: type_.group_id,
-                categoryID: type_.category_id,
-                capacity: type_.capacity.map(|x| x as f64),
-                mass: type_.mass.map(|x| x as f64),
-                volume: type_.volume.map(|x| x as f64),
-                radius: type_.radius.map(|x| x as f64),
-            },
-        }
+    fn type_name_suggestions(&self, name: &str) -> Vec<String> {
+        self.data.type_name_suggestions(name)
     }
 
-    fn type_name_to_id(&self, name: &str) -> i32 {
-        for (type_id, type_) in &self.data.types {
-            if type_.name == name {
-                return *type_id;
-            }
-        }
-        0
+    fn type_id_to_name(&self, type_id: i32) -> Option<String> {
+        self.data
+            .type_id_to_name(type_id, &self.language)
+            .map(|name| name.to_string())
     }
 }
 
-impl InfoMain<'_> {
-    pub fn new<'a>(fit: data_types::EsfFit, skills: BTreeMap<i32, i32>, data: &Data) -> InfoMain {
-        InfoMain { fit, skills, data }
+impl InfoSkills for InfoMain<'_> {
+    fn skill_type_ids(&self) -> Vec<i32> {
+        let mut skill_type_ids: Vec<i32> = self
+            .data
+            .types
+            .iter()
+            .filter(|(_, type_)| type_.category_id == 16 && type_.published) // Skill
+            .map(|(type_id, _)| *type_id)
+            .collect();
+        skill_type_ids.sort();
+        skill_type_ids
+    }
+
+    fn alpha_skill_level(&self, type_id: i32) -> i32 {
+        *self.data.alpha_skill_levels.get(&type_id).unwrap_or(&0)
     }
 }
 
-impl InfoNameMain<'_> {
-    pub fn new<'a>(data: &Data) -> InfoNameMain {
-        InfoNameMain { data }
+impl InfoMain<'_> {
+    pub fn new(data: &Data) -> InfoMain<'_> {
+        InfoMain::with_language(data, "en")
+    }
+
+    pub fn with_language<'a>(data: &'a Data, language: &str) -> InfoMain<'a> {
+        InfoMain {
+            data,
+            language: language.to_string(),
+        }
     }
 }
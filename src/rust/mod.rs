@@ -1,7 +1,26 @@
+mod batch;
+mod bundle;
+#[cfg(feature = "compact")]
+mod compact;
+mod diff;
+mod index;
 mod info;
 mod protobuf;
+#[cfg(feature = "sde")]
+mod sde;
+#[cfg(test)]
+mod test_data;
+mod validate;
 
-pub use info::{InfoMain, InfoNameMain};
+pub use batch::calculate_batch;
+pub use bundle::{bundle_sde_build, pack_bundle};
+#[cfg(feature = "compact")]
+pub use compact::{write_compact, CompactBuffer, CompactData};
+pub use diff::{
+    diff_data, diff_fit, diff_types, fit_type_ids, DataDiff, FitChange, TypeChange, ValueChange,
+};
+pub use index::NameIndex;
+pub use info::InfoMain;
 pub use protobuf::Data;
 
 pub mod esf_data {
@@ -1,26 +1,118 @@
-use std::{collections::HashMap, fs::File, io::Read, path::PathBuf};
+use std::{collections::HashMap, fs::File, io::Read, path::Path};
 
 use prost::Message;
+use serde::{de::DeserializeOwned, Deserialize};
 
 use super::esf_data;
+use super::index::NameIndex;
 
-fn load_protobuf<T: Message + std::default::Default>(
-    path: &PathBuf,
+pub(super) fn read_file(path: &Path, name: &str) -> Result<Vec<u8>, String> {
+    let filename = path.join(name);
+
+    let mut file =
+        File::open(&filename).map_err(|e| format!("Cannot open {}: {}", filename.display(), e))?;
+
+    let mut buf = Vec::new();
+    file.read_to_end(&mut buf)
+        .map_err(|e| format!("Cannot read {}: {}", filename.display(), e))?;
+
+    Ok(buf)
+}
+
+pub(super) fn decode_protobuf<T: Message + std::default::Default>(
+    buf: &[u8],
     name: &str,
 ) -> Result<T, String> {
-    let mut filename = path.join(name);
-    filename.set_extension("pb2");
+    T::decode(buf).map_err(|e| format!("Corrupt {}: {}", name, e))
+}
 
-    let mut file = File::open(filename).unwrap();
+fn load_protobuf<T: Message + std::default::Default>(path: &Path, name: &str) -> Result<T, String> {
+    let filename = format!("{}.pb2", name);
+    let buf = read_file(path, &filename)?;
 
-    let mut buf = Vec::new();
-    file.read_to_end(&mut buf).unwrap();
+    decode_protobuf(&buf, &path.join(filename).display().to_string())
+}
+
+#[allow(non_snake_case)]
+#[derive(Deserialize)]
+struct CloneGradeSkill {
+    typeID: i32,
+    level: i32,
+}
+
+#[derive(Deserialize)]
+pub(super) struct CloneGrade {
+    skills: Vec<CloneGradeSkill>,
+}
+
+/* Per skill, the highest level of any (Alpha) clone grade. */
+pub(super) fn alpha_skill_levels<'a>(
+    clone_grades: impl Iterator<Item = &'a CloneGrade>,
+) -> HashMap<i32, i32> {
+    let mut alpha_skill_levels = HashMap::new();
+
+    for clone_grade in clone_grades {
+        for skill in &clone_grade.skills {
+            let level = alpha_skill_levels.entry(skill.typeID).or_insert(0);
+            *level = (*level).max(skill.level);
+        }
+    }
+
+    alpha_skill_levels
+}
 
-    let object = T::decode(buf.as_slice());
-    match object {
-        Ok(object) => Ok(object),
-        Err(e) => Err(format!("Error: {:?}", e)),
+/* Data that is not part of the protobuf files (clone grades, localized type names and type
+ * metadata) is read from optional JSON files next to them. @eveshipfit/data doesn't ship
+ * these; without them, the data they provide is empty. A file that exists but doesn't
+ * parse is an error. */
+pub(super) fn parse_json<T: DeserializeOwned>(json: &[u8], name: &str) -> Result<T, String> {
+    serde_json::from_slice(json).map_err(|e| format!("Corrupt {}: {}", name, e))
+}
+
+fn load_optional_json<T: Default>(
+    path: &Path,
+    name: &str,
+    parse: impl Fn(&[u8], &str) -> Result<T, String>,
+) -> Result<T, String> {
+    let filename = path.join(name);
+    if !filename.exists() {
+        return Ok(T::default());
     }
+
+    parse(&read_file(path, name)?, &filename.display().to_string())
+}
+
+/* "cloneGrades.json": the SDE's fsd/cloneGrades.yaml as JSON:
+ * {"<gradeID>": {"skills": [{"typeID": .., "level": ..}]}}. */
+pub(super) fn parse_clone_grades(json: &[u8], name: &str) -> Result<HashMap<i32, i32>, String> {
+    let clone_grades: HashMap<String, CloneGrade> = parse_json(json, name)?;
+
+    Ok(alpha_skill_levels(clone_grades.values()))
+}
+
+pub(super) fn load_clone_grades(path: &Path) -> Result<HashMap<i32, i32>, String> {
+    load_optional_json(path, "cloneGrades.json", parse_clone_grades)
+}
+
+/* "typeNames.json": the "name" field of the SDE's types, as JSON:
+ * {"<typeID>": {"<language>": "<name>"}}. */
+fn load_localized_type_names(path: &Path) -> Result<HashMap<i32, HashMap<String, String>>, String> {
+    load_optional_json(path, "typeNames.json", parse_json)
+}
+
+/* "typeMetadata.json": names of groups and categories, and variation parents:
+ * {"groups": {"<groupID>": "<name>"}, "categories": {"<categoryID>": "<name>"},
+ * "variationParents": {"<typeID>": <parentTypeID>}}. */
+#[derive(Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub(super) struct TypeMetadata {
+    pub groups: HashMap<i32, String>,
+    pub categories: HashMap<i32, String>,
+    pub variation_parents: HashMap<i32, i32>,
+}
+
+fn load_type_metadata(path: &Path) -> Result<TypeMetadata, String> {
+    load_optional_json(path, "typeMetadata.json", parse_json)
 }
 
 pub struct Data {
@@ -28,21 +120,144 @@ pub struct Data {
     pub type_dogma: HashMap<i32, esf_data::type_dogma::TypeDogmaEntry>,
     pub dogma_attributes: HashMap<i32, esf_data::dogma_attributes::DogmaAttribute>,
     pub dogma_effects: HashMap<i32, esf_data::dogma_effects::DogmaEffect>,
+
+    /* Name to ID indices, built once at load time. */
+    pub type_names: NameIndex,
+    pub published_type_names: NameIndex,
+    /* Names in other languages are kept apart, so they never shadow an English name. */
+    pub localized_names: NameIndex,
+    pub published_localized_names: NameIndex,
+    pub attribute_names: NameIndex,
+
+    pub alpha_skill_levels: HashMap<i32, i32>,
+    /* Type ID -> language -> name; empty if no localizations are available. */
+    pub localized_type_names: HashMap<i32, HashMap<String, String>>,
+    /* Empty if no metadata is available. */
+    pub group_names: HashMap<i32, String>,
+    pub category_names: HashMap<i32, String>,
+    pub variation_parents: HashMap<i32, i32>,
+    /* Build number of the SDE the data is from, if known. */
+    pub sde_build: Option<u32>,
 }
 
 impl Data {
-    pub fn new(path: &PathBuf) -> Data {
-        let dogma_attributes: esf_data::DogmaAttributes =
-            load_protobuf(path, "dogmaAttributes").unwrap();
-        let dogma_effects: esf_data::DogmaEffects = load_protobuf(path, "dogmaEffects").unwrap();
-        let type_dogma: esf_data::TypeDogma = load_protobuf(path, "typeDogma").unwrap();
-        let types: esf_data::Types = load_protobuf(path, "types").unwrap();
-
-        Data {
-            types: types.entries,
-            type_dogma: type_dogma.entries,
-            dogma_attributes: dogma_attributes.entries,
-            dogma_effects: dogma_effects.entries,
+    /* Load the data files from the given directory. This only fails on missing or corrupt
+     * files; use validate() to check whether the files are consistent with each other. */
+    pub fn new(path: &Path) -> Result<Data, String> {
+        let dogma_attributes: esf_data::DogmaAttributes = load_protobuf(path, "dogmaAttributes")?;
+        let dogma_effects: esf_data::DogmaEffects = load_protobuf(path, "dogmaEffects")?;
+        let type_dogma: esf_data::TypeDogma = load_protobuf(path, "typeDogma")?;
+        let types: esf_data::Types = load_protobuf(path, "types")?;
+
+        Ok(Data::from_entries(
+            types.entries,
+            type_dogma.entries,
+            dogma_attributes.entries,
+            dogma_effects.entries,
+            load_clone_grades(path)?,
+            load_localized_type_names(path)?,
+            load_type_metadata(path)?,
+        ))
+    }
+
+    /* Build the indices on top of loaded entries. */
+    pub(super) fn from_entries(
+        types: HashMap<i32, esf_data::types::Type>,
+        type_dogma: HashMap<i32, esf_data::type_dogma::TypeDogmaEntry>,
+        dogma_attributes: HashMap<i32, esf_data::dogma_attributes::DogmaAttribute>,
+        dogma_effects: HashMap<i32, esf_data::dogma_effects::DogmaEffect>,
+        alpha_skill_levels: HashMap<i32, i32>,
+        localized_type_names: HashMap<i32, HashMap<String, String>>,
+        metadata: TypeMetadata,
+    ) -> Data {
+        let mut data = Data {
+            types,
+            type_dogma,
+            dogma_attributes,
+            dogma_effects,
+            type_names: NameIndex::default(),
+            published_type_names: NameIndex::default(),
+            localized_names: NameIndex::default(),
+            published_localized_names: NameIndex::default(),
+            attribute_names: NameIndex::default(),
+            alpha_skill_levels,
+            localized_type_names,
+            group_names: metadata.groups,
+            category_names: metadata.categories,
+            variation_parents: metadata.variation_parents,
+            sde_build: None,
+        };
+        data.build_indices();
+
+        data
+    }
+
+    fn build_indices(&mut self) {
+        for (type_id, type_) in &self.types {
+            self.type_names.insert(&type_.name, *type_id);
+            if type_.published {
+                self.published_type_names.insert(&type_.name, *type_id);
+            }
+
+            /* Names in all languages resolve to the type, so fits from any client can be read. */
+            for name in self
+                .localized_type_names
+                .get(type_id)
+                .into_iter()
+                .flat_map(|names| names.values())
+            {
+                self.localized_names.insert(name, *type_id);
+                if type_.published {
+                    self.published_localized_names.insert(name, *type_id);
+                }
+            }
         }
+
+        for (attribute_id, attribute) in &self.dogma_attributes {
+            self.attribute_names.insert(&attribute.name, *attribute_id);
+        }
+    }
+
+    /* Resolve an English type name to its ID, preferring published types over unpublished ones. */
+    pub fn type_name_to_id(&self, name: &str) -> Option<i32> {
+        self.published_type_names
+            .get(name)
+            .or_else(|| self.type_names.get(name))
+    }
+
+    /* Same as type_name_to_id(), but ignoring case and whitespace of the name. */
+    pub fn type_name_to_id_normalized(&self, name: &str) -> Option<i32> {
+        self.published_type_names
+            .get_normalized(name)
+            .or_else(|| self.type_names.get_normalized(name))
+    }
+
+    /* Resolve a type name in any other language; only used when the English lookups miss. */
+    pub fn localized_type_name_to_id(&self, name: &str) -> Option<i32> {
+        self.published_localized_names
+            .get(name)
+            .or_else(|| self.localized_names.get(name))
+            .or_else(|| self.published_localized_names.get_normalized(name))
+            .or_else(|| self.localized_names.get_normalized(name))
+    }
+
+    /* Published type names that look like the given name; for "did you mean" hints. */
+    pub fn type_name_suggestions(&self, name: &str) -> Vec<String> {
+        self.published_type_names.suggestions(name, 3)
+    }
+
+    /* Name of a type in the given language ("en", "de", "ru", ..), falling back to the English name. */
+    pub fn type_id_to_name(&self, type_id: i32, language: &str) -> Option<&str> {
+        let type_ = self.types.get(&type_id)?;
+
+        let localized_name = self
+            .localized_type_names
+            .get(&type_id)
+            .and_then(|names| names.get(language));
+        Some(localized_name.unwrap_or(&type_.name))
+    }
+
+    pub fn attribute_name_to_id(&self, name: &str) -> Option<i32> {
+        self.attribute_names.get(name)
     }
 }
@@ -0,0 +1,380 @@
+use std::{collections::HashMap, fs::File, io::BufRead, io::BufReader, path::Path};
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+use super::esf_data;
+use super::protobuf::{alpha_skill_levels, load_clone_grades, CloneGrade, TypeMetadata};
+use super::Data;
+
+#[allow(non_snake_case)]
+#[derive(Deserialize)]
+struct SdeType {
+    groupID: i32,
+    #[serde(default)]
+    name: HashMap<String, String>,
+    #[serde(default)]
+    published: bool,
+    factionID: Option<i32>,
+    marketGroupID: Option<i32>,
+    metaGroupID: Option<i32>,
+    variationParentTypeID: Option<i32>,
+    capacity: Option<f64>,
+    mass: Option<f64>,
+    radius: Option<f64>,
+    volume: Option<f64>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Deserialize)]
+struct SdeGroup {
+    categoryID: i32,
+    #[serde(default)]
+    name: HashMap<String, String>,
+}
+
+#[derive(Deserialize)]
+struct SdeCategory {
+    #[serde(default)]
+    name: HashMap<String, String>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Deserialize)]
+struct SdeTypeDogmaAttribute {
+    attributeID: i32,
+    value: f64,
+}
+
+#[allow(non_snake_case)]
+#[derive(Deserialize)]
+struct SdeTypeDogmaEffect {
+    effectID: i32,
+    #[serde(default)]
+    isDefault: bool,
+}
+
+#[allow(non_snake_case)]
+#[derive(Deserialize)]
+struct SdeTypeDogma {
+    #[serde(default)]
+    dogmaAttributes: Vec<SdeTypeDogmaAttribute>,
+    #[serde(default)]
+    dogmaEffects: Vec<SdeTypeDogmaEffect>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Deserialize)]
+struct SdeDogmaAttribute {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    published: bool,
+    #[serde(default)]
+    defaultValue: f64,
+    #[serde(default)]
+    highIsGood: bool,
+    #[serde(default)]
+    stackable: bool,
+}
+
+#[allow(non_snake_case)]
+#[derive(Deserialize)]
+struct SdeModifierInfo {
+    domain: String,
+    func: String,
+    modifiedAttributeID: Option<i32>,
+    modifyingAttributeID: Option<i32>,
+    operation: Option<i32>,
+    groupID: Option<i32>,
+    skillTypeID: Option<i32>,
+    effectID: Option<i32>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Deserialize)]
+struct SdeDogmaEffect {
+    #[serde(default, alias = "effectName")]
+    name: String,
+    #[serde(alias = "effectCategoryID")]
+    effectCategory: i32,
+    #[serde(default)]
+    electronicChance: bool,
+    #[serde(default)]
+    isAssistance: bool,
+    #[serde(default)]
+    isOffensive: bool,
+    #[serde(default)]
+    isWarpSafe: bool,
+    #[serde(default)]
+    propulsionChance: bool,
+    #[serde(default)]
+    rangeChance: bool,
+    dischargeAttributeID: Option<i32>,
+    durationAttributeID: Option<i32>,
+    rangeAttributeID: Option<i32>,
+    falloffAttributeID: Option<i32>,
+    trackingSpeedAttributeID: Option<i32>,
+    fittingUsageChanceAttributeID: Option<i32>,
+    resistanceAttributeID: Option<i32>,
+    #[serde(default)]
+    modifierInfo: Vec<SdeModifierInfo>,
+}
+
+/* A line of a JSONL file; the ID is in "_key", the rest are the fields of the entry. */
+#[derive(Deserialize)]
+struct SdeJsonlEntry<T> {
+    #[serde(rename = "_key")]
+    key: i32,
+    #[serde(flatten)]
+    value: T,
+}
+
+/* Load "<name>.jsonl" (the current SDE format) or, if that doesn't exist,
+ * "<name>.yaml" (the older SDE format); both are keyed by ID. */
+fn load_sde_file<T: DeserializeOwned>(path: &Path, name: &str) -> Result<HashMap<i32, T>, String> {
+    let filename = path.join(format!("{}.jsonl", name));
+    if let Ok(file) = File::open(&filename) {
+        let mut entries = HashMap::new();
+
+        for (index, line) in BufReader::new(file).lines().enumerate() {
+            let line = line.map_err(|e| format!("Cannot read {}: {}", filename.display(), e))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let entry: SdeJsonlEntry<T> = serde_json::from_str(&line).map_err(|e| {
+                format!("Corrupt {} (line {}): {}", filename.display(), index + 1, e)
+            })?;
+            entries.insert(entry.key, entry.value);
+        }
+
+        return Ok(entries);
+    }
+
+    let filename = path.join(format!("{}.yaml", name));
+    let file = File::open(&filename).map_err(|e| {
+        format!(
+            "Cannot open {}.jsonl or {}.yaml in {}: {}",
+            name,
+            name,
+            path.display(),
+            e
+        )
+    })?;
+    serde_yaml::from_reader(BufReader::new(file))
+        .map_err(|e| format!("Corrupt {}: {}", filename.display(), e))
+}
+
+/* Same as load_sde_file(), but a file that doesn't exist in either format is empty. */
+fn load_optional_sde_file<T: DeserializeOwned>(
+    path: &Path,
+    name: &str,
+) -> Result<HashMap<i32, T>, String> {
+    let exists = ["jsonl", "yaml"]
+        .iter()
+        .any(|extension| path.join(format!("{}.{}", name, extension)).exists());
+
+    match exists {
+        true => load_sde_file(path, name),
+        false => Ok(HashMap::new()),
+    }
+}
+
+fn domain_to_id(domain: &str) -> Option<i32> {
+    match domain {
+        "itemID" => Some(0),
+        "shipID" => Some(1),
+        "charID" => Some(2),
+        "otherID" => Some(3),
+        "structureID" => Some(4),
+        "target" => Some(5),
+        "targetID" => Some(6),
+        _ => None,
+    }
+}
+
+fn func_to_id(func: &str) -> Option<i32> {
+    match func {
+        "ItemModifier" => Some(0),
+        "LocationGroupModifier" => Some(1),
+        "LocationModifier" => Some(2),
+        "LocationRequiredSkillModifier" => Some(3),
+        "OwnerRequiredSkillModifier" => Some(4),
+        "EffectStopper" => Some(5),
+        _ => None,
+    }
+}
+
+fn convert_effect(effect: SdeDogmaEffect) -> esf_data::dogma_effects::DogmaEffect {
+    /* Modifiers the engine doesn't know (like fleet bonuses) are left out, the same
+     * as the EVEShipFit data files do. */
+    let modifier_info = effect
+        .modifierInfo
+        .into_iter()
+        .filter_map(|modifier| {
+            Some(esf_data::dogma_effects::dogma_effect::ModifierInfo {
+                domain: domain_to_id(&modifier.domain)?,
+                func: func_to_id(&modifier.func)?,
+                modified_attribute_id: modifier.modifiedAttributeID,
+                modifying_attribute_id: modifier.modifyingAttributeID,
+                operation: modifier.operation,
+                group_id: modifier.groupID,
+                skill_type_id: modifier.skillTypeID,
+                effect_id: modifier.effectID,
+            })
+        })
+        .collect();
+
+    esf_data::dogma_effects::DogmaEffect {
+        name: effect.name,
+        effect_category: effect.effectCategory,
+        electronic_chance: effect.electronicChance,
+        is_assistance: effect.isAssistance,
+        is_offensive: effect.isOffensive,
+        is_warp_safe: effect.isWarpSafe,
+        propulsion_chance: effect.propulsionChance,
+        range_chance: effect.rangeChance,
+        discharge_attribute_id: effect.dischargeAttributeID,
+        duration_attribute_id: effect.durationAttributeID,
+        range_attribute_id: effect.rangeAttributeID,
+        falloff_attribute_id: effect.falloffAttributeID,
+        tracking_speed_attribute_id: effect.trackingSpeedAttributeID,
+        fitting_usage_chance_attribute_id: effect.fittingUsageChanceAttributeID,
+        resistance_attribute_id: effect.resistanceAttributeID,
+        modifier_info,
+    }
+}
+
+impl Data {
+    /* Load the data directly from CCP's SDE (types, groups, typeDogma, dogmaAttributes,
+     * dogmaEffects and, if available, categories and cloneGrades), instead of from EVEShipFit's
+     * protobuf files. Type names are read in all languages.
+     *
+     * EVEShipFit's own attributes and effects (with negative IDs, like ehp,
+     * capacitorPeakDelta and damagePerSecondWithoutReload) are added by its data pipeline,
+     * and are not part of the SDE; they cannot be derived here. Attributes of the fit are
+     * calculated as usual, but the statistics based on them are not available. */
+    pub fn from_sde(path: &Path) -> Result<Data, String> {
+        let categories: HashMap<i32, SdeCategory> = load_optional_sde_file(path, "categories")?;
+        let groups: HashMap<i32, SdeGroup> = load_sde_file(path, "groups")?;
+        let sde_types: HashMap<i32, SdeType> = load_sde_file(path, "types")?;
+        let sde_type_dogma: HashMap<i32, SdeTypeDogma> = load_sde_file(path, "typeDogma")?;
+        let sde_dogma_attributes: HashMap<i32, SdeDogmaAttribute> =
+            load_sde_file(path, "dogmaAttributes")?;
+        let sde_dogma_effects: HashMap<i32, SdeDogmaEffect> = load_sde_file(path, "dogmaEffects")?;
+        let clone_grades: HashMap<i32, CloneGrade> = load_optional_sde_file(path, "cloneGrades")?;
+
+        let mut types = HashMap::new();
+        let mut localized_type_names = HashMap::new();
+        let mut variation_parents = HashMap::new();
+        for (type_id, type_) in sde_types {
+            let category_id = match groups.get(&type_.groupID) {
+                Some(group) => group.categoryID,
+                None => {
+                    return Err(format!(
+                        "types: type {} has unknown group {}",
+                        type_id, type_.groupID
+                    ))
+                }
+            };
+
+            types.insert(
+                type_id,
+                esf_data::types::Type {
+                    name: type_.name.get("en").cloned().unwrap_or_default(),
+                    group_id: type_.groupID,
+                    category_id,
+                    published: type_.published,
+                    faction_id: type_.factionID,
+                    market_group_id: type_.marketGroupID,
+                    meta_group_id: type_.metaGroupID,
+                    capacity: type_.capacity.map(|x| x as f32),
+                    mass: type_.mass.map(|x| x as f32),
+                    radius: type_.radius.map(|x| x as f32),
+                    volume: type_.volume.map(|x| x as f32),
+                },
+            );
+            localized_type_names.insert(type_id, type_.name);
+            if let Some(parent_type_id) = type_.variationParentTypeID {
+                variation_parents.insert(type_id, parent_type_id);
+            }
+        }
+
+        let metadata = TypeMetadata {
+            groups: groups
+                .into_iter()
+                .filter_map(|(group_id, group)| Some((group_id, group.name.get("en")?.clone())))
+                .collect(),
+            categories: categories
+                .into_iter()
+                .filter_map(|(category_id, category)| {
+                    Some((category_id, category.name.get("en")?.clone()))
+                })
+                .collect(),
+            variation_parents,
+        };
+
+        let type_dogma = sde_type_dogma
+            .into_iter()
+            .map(|(type_id, type_dogma)| {
+                let entry = esf_data::type_dogma::TypeDogmaEntry {
+                    dogma_attributes: type_dogma
+                        .dogmaAttributes
+                        .into_iter()
+                        .map(
+                            |attribute| esf_data::type_dogma::type_dogma_entry::DogmaAttributes {
+                                attribute_id: attribute.attributeID,
+                                value: attribute.value as f32,
+                            },
+                        )
+                        .collect(),
+                    dogma_effects: type_dogma
+                        .dogmaEffects
+                        .into_iter()
+                        .map(
+                            |effect| esf_data::type_dogma::type_dogma_entry::DogmaEffects {
+                                effect_id: effect.effectID,
+                                is_default: effect.isDefault,
+                            },
+                        )
+                        .collect(),
+                };
+                (type_id, entry)
+            })
+            .collect();
+
+        let dogma_attributes = sde_dogma_attributes
+            .into_iter()
+            .map(|(attribute_id, attribute)| {
+                let attribute = esf_data::dogma_attributes::DogmaAttribute {
+                    name: attribute.name,
+                    published: attribute.published,
+                    default_value: attribute.defaultValue as f32,
+                    high_is_good: attribute.highIsGood,
+                    stackable: attribute.stackable,
+                };
+                (attribute_id, attribute)
+            })
+            .collect();
+
+        let dogma_effects = sde_dogma_effects
+            .into_iter()
+            .map(|(effect_id, effect)| (effect_id, convert_effect(effect)))
+            .collect();
+
+        Ok(Data::from_entries(
+            types,
+            type_dogma,
+            dogma_attributes,
+            dogma_effects,
+            /* Older exports of the SDE have no clone grades; fall back to cloneGrades.json. */
+            match clone_grades.is_empty() {
+                true => load_clone_grades(path)?,
+                false => alpha_skill_levels(clone_grades.values()),
+            },
+            localized_type_names,
+            metadata,
+        ))
+    }
+}
@@ -0,0 +1,177 @@
+use std::path::{Path, PathBuf};
+
+use prost::Message;
+
+use super::esf_data::{self, dogma_attributes, dogma_effects, type_dogma, types};
+
+/* A directory with a tiny set of data files, for the tests of the data formats. Every test
+ * uses its own directory, so tests can run in parallel; it is removed when dropped. */
+pub struct TestDataDir {
+    pub path: PathBuf,
+}
+
+impl Drop for TestDataDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
+
+fn write(path: &Path, name: &str, content: &[u8]) {
+    std::fs::write(path.join(name), content).unwrap();
+}
+
+fn type_(name: &str, group_id: i32, category_id: i32, published: bool) -> types::Type {
+    types::Type {
+        name: name.to_string(),
+        group_id,
+        category_id,
+        published,
+        ..Default::default()
+    }
+}
+
+fn attribute(name: &str, default_value: f32) -> dogma_attributes::DogmaAttribute {
+    dogma_attributes::DogmaAttribute {
+        name: name.to_string(),
+        published: true,
+        default_value,
+        high_is_good: true,
+        stackable: false,
+    }
+}
+
+/* A frigate (and an unpublished type with the same name), a gun, a charge and a skill,
+ * with a few attributes and effects. Optional values are left out on purpose, as the
+ * formats have to keep them apart from zero. With `optional_files`, the JSON files
+ * with clone grades, localized names and metadata are written too. */
+pub fn test_data_dir(name: &str, optional_files: bool) -> TestDataDir {
+    let path = std::env::temp_dir().join(format!("esf-test-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&path);
+    std::fs::create_dir_all(&path).unwrap();
+
+    let mut rifter = type_("Rifter", 25, 6, true);
+    rifter.market_group_id = Some(64);
+    rifter.capacity = Some(140.0);
+    rifter.mass = Some(1_067_000.0);
+    rifter.radius = Some(31.0);
+    let mut gun = type_("125mm Gatling AutoCannon I", 55, 7, true);
+    gun.meta_group_id = Some(1);
+    gun.volume = Some(5.0);
+
+    let types = esf_data::Types {
+        entries: [
+            (587, rifter),
+            (588, type_("Rifter", 25, 6, false)),
+            (484, gun),
+            (185, type_("EMP S", 83, 8, true)),
+            (3300, type_("Gunnery", 255, 16, true)),
+        ]
+        .into(),
+    };
+
+    let type_dogma = esf_data::TypeDogma {
+        entries: [
+            (
+                587,
+                type_dogma::TypeDogmaEntry {
+                    dogma_attributes: vec![type_dogma::type_dogma_entry::DogmaAttributes {
+                        attribute_id: 9,
+                        value: 350.0,
+                    }],
+                    dogma_effects: vec![],
+                },
+            ),
+            (
+                484,
+                type_dogma::TypeDogmaEntry {
+                    dogma_attributes: vec![type_dogma::type_dogma_entry::DogmaAttributes {
+                        attribute_id: 128,
+                        value: 1.0,
+                    }],
+                    dogma_effects: vec![
+                        type_dogma::type_dogma_entry::DogmaEffects {
+                            effect_id: 12,
+                            is_default: false,
+                        },
+                        type_dogma::type_dogma_entry::DogmaEffects {
+                            effect_id: 34,
+                            is_default: true,
+                        },
+                    ],
+                },
+            ),
+        ]
+        .into(),
+    };
+
+    let dogma_attributes = esf_data::DogmaAttributes {
+        entries: [
+            (9, attribute("hp", 0.0)),
+            (128, attribute("chargeSize", 1.0)),
+        ]
+        .into(),
+    };
+
+    let dogma_effects = esf_data::DogmaEffects {
+        entries: [
+            (
+                12,
+                dogma_effects::DogmaEffect {
+                    name: "hiPower".to_string(),
+                    ..Default::default()
+                },
+            ),
+            (
+                34,
+                dogma_effects::DogmaEffect {
+                    name: "projectileFired".to_string(),
+                    effect_category: 1,
+                    is_offensive: true,
+                    discharge_attribute_id: Some(6),
+                    duration_attribute_id: Some(51),
+                    modifier_info: vec![dogma_effects::dogma_effect::ModifierInfo {
+                        domain: 1,
+                        func: 1,
+                        modified_attribute_id: Some(9),
+                        modifying_attribute_id: Some(128),
+                        operation: Some(6),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                },
+            ),
+        ]
+        .into(),
+    };
+
+    write(&path, "types.pb2", &types.encode_to_vec());
+    write(&path, "typeDogma.pb2", &type_dogma.encode_to_vec());
+    write(
+        &path,
+        "dogmaAttributes.pb2",
+        &dogma_attributes.encode_to_vec(),
+    );
+    write(&path, "dogmaEffects.pb2", &dogma_effects.encode_to_vec());
+
+    if optional_files {
+        write(
+            &path,
+            "cloneGrades.json",
+            br#"{"1": {"skills": [{"typeID": 3300, "level": 3}]},
+                 "2": {"skills": [{"typeID": 3300, "level": 4}]}}"#,
+        );
+        write(
+            &path,
+            "typeNames.json",
+            r#"{"587": {"en": "Rifter", "ja": "リフター"}}"#.as_bytes(),
+        );
+        write(
+            &path,
+            "typeMetadata.json",
+            br#"{"groups": {"25": "Frigate"}, "categories": {"6": "Ship"},
+                 "variationParents": {"484": 483}}"#,
+        );
+    }
+
+    TestDataDir { path }
+}
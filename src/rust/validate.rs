@@ -0,0 +1,73 @@
+use super::Data;
+
+impl Data {
+    /* Check that the four data files reference each other correctly: every type with
+     * dogma exists, and every attribute / effect referenced exists. This catches
+     * files from different SDE versions being mixed. Returns the problems found, sorted. */
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        for (type_id, type_dogma) in &self.type_dogma {
+            if !self.types.contains_key(type_id) {
+                problems.push(format!("typeDogma: unknown type {}", type_id));
+            }
+
+            for attribute in &type_dogma.dogma_attributes {
+                if !self.dogma_attributes.contains_key(&attribute.attribute_id) {
+                    problems.push(format!(
+                        "typeDogma: type {} has unknown attribute {}",
+                        type_id, attribute.attribute_id
+                    ));
+                }
+            }
+
+            for effect in &type_dogma.dogma_effects {
+                if !self.dogma_effects.contains_key(&effect.effect_id) {
+                    problems.push(format!(
+                        "typeDogma: type {} has unknown effect {}",
+                        type_id, effect.effect_id
+                    ));
+                }
+            }
+        }
+
+        for (effect_id, effect) in &self.dogma_effects {
+            let mut attribute_ids = vec![
+                effect.discharge_attribute_id,
+                effect.duration_attribute_id,
+                effect.range_attribute_id,
+                effect.falloff_attribute_id,
+                effect.tracking_speed_attribute_id,
+                effect.fitting_usage_chance_attribute_id,
+                effect.resistance_attribute_id,
+            ];
+            for modifier in &effect.modifier_info {
+                attribute_ids.push(modifier.modified_attribute_id);
+                attribute_ids.push(modifier.modifying_attribute_id);
+
+                if let Some(stopped_effect_id) = modifier.effect_id {
+                    if !self.dogma_effects.contains_key(&stopped_effect_id) {
+                        problems.push(format!(
+                            "dogmaEffects: effect {} references unknown effect {}",
+                            effect_id, stopped_effect_id
+                        ));
+                    }
+                }
+            }
+
+            for attribute_id in attribute_ids.into_iter().flatten() {
+                if !self.dogma_attributes.contains_key(&attribute_id) {
+                    problems.push(format!(
+                        "dogmaEffects: effect {} references unknown attribute {}",
+                        effect_id, attribute_id
+                    ));
+                }
+            }
+        }
+
+        problems.sort();
+        problems.dedup();
+
+        problems
+    }
+}
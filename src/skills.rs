@@ -0,0 +1,69 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use crate::info::InfoSkills;
+
+/* Built-in skill profiles; a partial skill map can be overlaid on any of them. */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkillProfile {
+    All0,
+    AllIV,
+    AllV,
+    Alpha,
+}
+
+impl FromStr for SkillProfile {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<SkillProfile, String> {
+        match value.to_lowercase().as_str() {
+            "all-0" | "all0" => Ok(SkillProfile::All0),
+            "all-4" | "all-iv" | "alliv" => Ok(SkillProfile::AllIV),
+            "all-5" | "all-v" | "allv" => Ok(SkillProfile::AllV),
+            "alpha" => Ok(SkillProfile::Alpha),
+            _ => Err(format!(
+                "Unknown skill profile '{}'; expected all-0, all-iv, all-v or alpha",
+                value
+            )),
+        }
+    }
+}
+
+/* Build the skill levels for a profile, and overlay the given (partial) skill
+ * map on top of it. Skills in the overlay win over the profile; for the Alpha
+ * profile they are still capped at the level an Alpha clone can train. */
+pub fn skill_profile(
+    info: &impl InfoSkills,
+    profile: SkillProfile,
+    overlay: &BTreeMap<i32, i32>,
+) -> Result<BTreeMap<i32, i32>, String> {
+    let mut skills = BTreeMap::new();
+
+    for skill_id in info.skill_type_ids() {
+        let level = match profile {
+            SkillProfile::All0 => 0,
+            SkillProfile::AllIV => 4,
+            SkillProfile::AllV => 5,
+            SkillProfile::Alpha => info.alpha_skill_level(skill_id),
+        };
+        skills.insert(skill_id, level);
+    }
+
+    /* Without clone grade data every skill would silently end up at L0. */
+    if profile == SkillProfile::Alpha && skills.values().all(|level| *level == 0) {
+        return Err(
+            "No alpha clone skill data available; this needs the SDE's clone grades (cloneGrades.json next to the data files)"
+                .to_string(),
+        );
+    }
+
+    for (skill_id, level) in overlay {
+        let level = match profile {
+            SkillProfile::Alpha => (*level).min(info.alpha_skill_level(*skill_id)),
+            _ => *level,
+        };
+        skills.insert(*skill_id, level);
+    }
+
+    Ok(skills)
+}
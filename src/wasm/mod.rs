@@ -1,4 +1,3 @@
-use serde_wasm_bindgen;
 use std::collections::BTreeMap;
 use wasm_bindgen::prelude::*;
 
@@ -6,6 +5,8 @@ use crate::calculate;
 use crate::data_types;
 use crate::info::Info;
 use crate::info::InfoName;
+use crate::info::InfoSkills;
+use crate::skills;
 
 #[wasm_bindgen]
 extern "C" {
@@ -27,15 +28,21 @@ extern "C" {
     #[wasm_bindgen(js_namespace = window)]
     fn type_name_to_id(name: &str) -> JsValue;
 
+    #[wasm_bindgen(js_namespace = window)]
+    fn type_id_to_name(type_id: i32) -> JsValue;
+
     #[wasm_bindgen(js_namespace = window)]
     fn attribute_name_to_id(name: &str) -> JsValue;
-}
 
-pub struct InfoWasm {
-    pub fit: data_types::EsfFit,
-    pub skills: BTreeMap<i32, i32>,
+    #[wasm_bindgen(js_namespace = window)]
+    fn get_skill_type_ids() -> JsValue;
+
+    #[wasm_bindgen(js_namespace = window)]
+    fn get_alpha_skill_level(type_id: i32) -> JsValue;
 }
 
+pub struct InfoWasm {}
+
 impl Info for InfoWasm {
     fn get_dogma_attributes(&self, type_id: i32) -> Vec<data_types::TypeDogmaAttribute> {
         let js = get_dogma_attributes(type_id);
@@ -66,36 +73,35 @@ impl Info for InfoWasm {
         let js = attribute_name_to_id(name);
         serde_wasm_bindgen::from_value(js).unwrap()
     }
-
-    fn skills(&self) -> &BTreeMap<i32, i32> {
-        &self.skills
-    }
-
-    fn fit(&self) -> &data_types::EsfFit {
-        &self.fit
-    }
 }
 
 impl InfoName for InfoWasm {
-    fn get_dogma_effects(&self, type_id: i32) -> Vec<data_types::TypeDogmaEffect> {
-        let js = get_dogma_effects(type_id);
+    fn type_name_to_id(&self, name: &str) -> i32 {
+        let js = type_name_to_id(name);
         serde_wasm_bindgen::from_value(js).unwrap()
     }
 
-    fn get_type(&self, type_id: i32) -> data_types::Type {
-        let js = get_type(type_id);
+    fn type_id_to_name(&self, type_id: i32) -> Option<String> {
+        let js = type_id_to_name(type_id);
         serde_wasm_bindgen::from_value(js).unwrap()
     }
+}
 
-    fn type_name_to_id(&self, name: &str) -> i32 {
-        let js = type_name_to_id(name);
+impl InfoSkills for InfoWasm {
+    fn skill_type_ids(&self) -> Vec<i32> {
+        let js = get_skill_type_ids();
+        serde_wasm_bindgen::from_value(js).unwrap()
+    }
+
+    fn alpha_skill_level(&self, type_id: i32) -> i32 {
+        let js = get_alpha_skill_level(type_id);
         serde_wasm_bindgen::from_value(js).unwrap()
     }
 }
 
 impl InfoWasm {
-    pub fn new<'a>(fit: data_types::EsfFit, skills: BTreeMap<i32, i32>) -> InfoWasm {
-        InfoWasm { fit, skills }
+    pub fn new() -> InfoWasm {
+        InfoWasm {}
     }
 }
 
@@ -113,8 +119,31 @@ pub fn calculate(js_esf_fit: JsValue, js_skills: JsValue) -> JsValue {
         .map(|(k, v)| (k.parse::<i32>().unwrap(), v))
         .collect();
 
-    let info = InfoWasm::new(fit, skills);
+    let info = InfoWasm::new();
+    let input = calculate::Input::new(fit, skills);
 
-    let statistics = calculate::calculate(&info);
+    let statistics = calculate::calculate(&info, &input);
     serde_wasm_bindgen::to_value(&statistics).unwrap()
 }
+
+#[wasm_bindgen]
+pub fn calculate_with_skill_profile(
+    js_esf_fit: JsValue,
+    skill_profile: &str,
+    js_skills: JsValue,
+) -> Result<JsValue, JsValue> {
+    let fit: data_types::EsfFit = serde_wasm_bindgen::from_value(js_esf_fit).unwrap();
+    let skills: BTreeMap<String, i32> = serde_wasm_bindgen::from_value(js_skills).unwrap();
+    let skills = skills
+        .into_iter()
+        .map(|(k, v)| (k.parse::<i32>().unwrap(), v))
+        .collect();
+
+    let info = InfoWasm::new();
+    let skill_profile: skills::SkillProfile = skill_profile.parse()?;
+    let skills = skills::skill_profile(&info, skill_profile, &skills)?;
+    let input = calculate::Input::new(fit, skills);
+
+    let statistics = calculate::calculate(&info, &input);
+    Ok(serde_wasm_bindgen::to_value(&statistics).unwrap())
+}
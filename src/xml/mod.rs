@@ -0,0 +1,251 @@
+use crate::data_types;
+use crate::fit_common::{lookup_type, type_name};
+use crate::info::InfoName;
+
+/* (slot name in the XML, slot type) of all module slots, in the order the client writes them. */
+const SLOT_NAMES: [(&str, data_types::EsfSlotType); 6] = [
+    ("low slot", data_types::EsfSlotType::Low),
+    ("med slot", data_types::EsfSlotType::Medium),
+    ("hi slot", data_types::EsfSlotType::High),
+    ("rig slot", data_types::EsfSlotType::Rig),
+    ("subsystem slot", data_types::EsfSlotType::SubSystem),
+    ("service slot", data_types::EsfSlotType::Service),
+];
+
+pub struct XmlFit {
+    pub name: String,
+    pub description: String,
+    pub esf_fit: data_types::EsfFit,
+    pub cargo: Vec<data_types::EsfCargo>,
+}
+
+/* "hi slot 3" -> (High, 3). */
+fn parse_slot(slot: &str) -> Option<data_types::EsfSlot> {
+    SLOT_NAMES.iter().find_map(|(name, slot_type)| {
+        let index = slot.strip_prefix(name)?.trim().parse::<i32>().ok()?;
+        Some(data_types::EsfSlot {
+            r#type: *slot_type,
+            index,
+        })
+    })
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/* Add a quantity of a type, merging it with an earlier entry of the same type. */
+fn add_quantity(entries: &mut Vec<(i32, i32)>, type_id: i32, quantity: i32) {
+    match entries
+        .iter_mut()
+        .find(|(entry_type_id, _)| *entry_type_id == type_id)
+    {
+        Some((_, entry_quantity)) => *entry_quantity += quantity,
+        None => entries.push((type_id, quantity)),
+    }
+}
+
+fn load_hardware(
+    info: &impl InfoName,
+    hardware: roxmltree::Node,
+    xml_fit: &mut XmlFit,
+) -> Result<(), String> {
+    let type_name = hardware
+        .attribute("type")
+        .ok_or("Hardware without type".to_string())?;
+    let slot = hardware
+        .attribute("slot")
+        .ok_or(format!("Hardware '{}' without slot", type_name))?;
+    let type_id = lookup_type(info, type_name)?;
+
+    let quantity = match hardware.attribute("qty") {
+        Some(quantity) => match quantity.parse::<i32>() {
+            Ok(quantity) if quantity > 0 => quantity,
+            _ => {
+                return Err(format!(
+                    "Invalid quantity '{}' for '{}'",
+                    quantity, type_name
+                ))
+            }
+        },
+        None => 1,
+    };
+
+    match slot {
+        "drone bay" => {
+            for _ in 0..quantity {
+                xml_fit.esf_fit.drones.push(data_types::EsfDrone {
+                    type_id,
+                    state: data_types::EsfState::Active,
+                });
+            }
+        }
+        "cargo" => xml_fit
+            .cargo
+            .push(data_types::EsfCargo { type_id, quantity }),
+        _ => {
+            let Some(slot) = parse_slot(slot) else {
+                return Err(format!("Unknown slot '{}' for '{}'", slot, type_name));
+            };
+
+            xml_fit.esf_fit.modules.push(data_types::EsfModule {
+                type_id,
+                slot,
+                state: data_types::EsfState::Active,
+                charge: None,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+fn load_fitting(info: &impl InfoName, fitting: roxmltree::Node) -> Result<XmlFit, String> {
+    let name = fitting.attribute("name").unwrap_or_default().to_string();
+
+    let mut xml_fit = XmlFit {
+        name: name.clone(),
+        description: String::new(),
+        esf_fit: data_types::EsfFit {
+            ship_type_id: 0,
+            modules: Vec::new(),
+            drones: Vec::new(),
+            implants: Vec::new(),
+            boosters: Vec::new(),
+            mode: None,
+        },
+        cargo: Vec::new(),
+    };
+
+    for node in fitting.children().filter(|node| node.is_element()) {
+        let value = node.attribute("value").unwrap_or_default();
+
+        match node.tag_name().name() {
+            "description" => xml_fit.description = value.to_string(),
+            "shipType" => xml_fit.esf_fit.ship_type_id = lookup_type(info, value)?,
+            "hardware" => load_hardware(info, node, &mut xml_fit)?,
+            _ => {}
+        }
+    }
+
+    if xml_fit.esf_fit.ship_type_id == 0 {
+        return Err("Fitting without shipType".to_string());
+    }
+
+    Ok(xml_fit)
+}
+
+/* Load an XML string as exported by the game client, and return all fits in it.
+ * Each fit either loads or has its own error, so one broken fit doesn't hide the others.
+ *
+ * The client doesn't export which charge is loaded in which module, nor the
+ * state of modules; charges stay in the cargo and modules are set to Active. */
+pub fn load_xml(info: &impl InfoName, xml: &str) -> Vec<Result<XmlFit, String>> {
+    let document = match roxmltree::Document::parse(xml) {
+        Ok(document) => document,
+        Err(e) => return vec![Err(format!("Invalid XML: {}", e))],
+    };
+
+    let root = document.root_element();
+    if root.tag_name().name() != "fittings" {
+        return vec![Err(format!(
+            "Expected <fittings>, found <{}>",
+            root.tag_name().name()
+        ))];
+    }
+
+    root.children()
+        .filter(|node| node.is_element() && node.tag_name().name() == "fitting")
+        .map(|fitting| {
+            load_fitting(info, fitting).map_err(|e| {
+                format!(
+                    "Fitting '{}': {}",
+                    fitting.attribute("name").unwrap_or_default(),
+                    e
+                )
+            })
+        })
+        .collect()
+}
+
+fn save_fitting(info: &impl InfoName, xml_fit: &XmlFit) -> Result<String, String> {
+    let esf_fit = &xml_fit.esf_fit;
+
+    let mut xml = format!("  <fitting name=\"{}\">\n", escape(&xml_fit.name));
+    xml += &format!(
+        "    <description value=\"{}\"/>\n",
+        escape(&xml_fit.description)
+    );
+    xml += &format!(
+        "    <shipType value=\"{}\"/>\n",
+        escape(&type_name(info, esf_fit.ship_type_id)?)
+    );
+
+    for (slot_name, slot_type) in &SLOT_NAMES {
+        let mut modules: Vec<&data_types::EsfModule> = esf_fit
+            .modules
+            .iter()
+            .filter(|module| module.slot.r#type == *slot_type)
+            .collect();
+        modules.sort_by_key(|module| module.slot.index);
+
+        for module in modules {
+            xml += &format!(
+                "    <hardware slot=\"{} {}\" type=\"{}\"/>\n",
+                slot_name,
+                module.slot.index,
+                escape(&type_name(info, module.type_id)?)
+            );
+        }
+    }
+
+    let mut drones = Vec::new();
+    for drone in &esf_fit.drones {
+        add_quantity(&mut drones, drone.type_id, 1);
+    }
+
+    /* Loaded charges are written as cargo, the same as the game client saves them. */
+    let mut cargo = Vec::new();
+    for module in &esf_fit.modules {
+        if let Some(charge) = &module.charge {
+            add_quantity(&mut cargo, charge.type_id, 1);
+        }
+    }
+    for item in &xml_fit.cargo {
+        add_quantity(&mut cargo, item.type_id, item.quantity);
+    }
+
+    for (slot, (type_id, quantity)) in drones
+        .into_iter()
+        .map(|drone| ("drone bay", drone))
+        .chain(cargo.into_iter().map(|cargo| ("cargo", cargo)))
+    {
+        xml += &format!(
+            "    <hardware qty=\"{}\" slot=\"{}\" type=\"{}\"/>\n",
+            quantity,
+            slot,
+            escape(&type_name(info, type_id)?)
+        );
+    }
+
+    xml += "  </fitting>\n";
+    Ok(xml)
+}
+
+/* Write ESF fits as XML string, the same way the game client does. The client
+ * format has no room for module states, implants, boosters or ship modes; those
+ * are not written. Loaded charges are written as cargo. */
+pub fn save_xml(info: &impl InfoName, xml_fits: &[XmlFit]) -> Result<String, String> {
+    let mut xml = "<?xml version=\"1.0\" ?>\n<fittings>\n".to_string();
+
+    for xml_fit in xml_fits {
+        xml += &save_fitting(info, xml_fit)?;
+    }
+
+    xml += "</fittings>\n";
+    Ok(xml)
+}
@@ -0,0 +1,300 @@
+/* A small, hand-written universe implementing the Info traits, so the engine and the
+ * fit formats can be tested without the data-files. */
+#![allow(dead_code)]
+
+use std::collections::BTreeMap;
+
+use esf_dogma_engine::data_types;
+use esf_dogma_engine::info::{Info, InfoName};
+
+pub const ATTRIBUTE_CHARGE_SIZE_ID: i32 = 128;
+pub const ATTRIBUTE_CHARGE_GROUP1_ID: i32 = 604;
+
+pub const EFFECT_LO_POWER_ID: i32 = 11;
+pub const EFFECT_HI_POWER_ID: i32 = 12;
+pub const EFFECT_MED_POWER_ID: i32 = 13;
+pub const EFFECT_RIG_SLOT_ID: i32 = 2663;
+pub const EFFECT_SERVICE_SLOT_ID: i32 = 6306;
+
+pub struct TestType {
+    pub name: String,
+    pub group_id: i32,
+    pub category_id: i32,
+    pub attributes: Vec<(i32, f64)>,
+    pub effects: Vec<i32>,
+}
+
+pub struct TestModifier {
+    pub domain: i32,
+    pub func: i32,
+    pub modified_attribute_id: Option<i32>,
+    pub modifying_attribute_id: Option<i32>,
+    pub operation: Option<i32>,
+    pub effect_id: Option<i32>,
+}
+
+pub struct TestEffect {
+    pub category: i32,
+    pub modifiers: Vec<TestModifier>,
+}
+
+#[derive(Default)]
+pub struct TestInfo {
+    pub types: BTreeMap<i32, TestType>,
+    pub effects: BTreeMap<i32, TestEffect>,
+}
+
+impl TestInfo {
+    pub fn add_type(
+        &mut self,
+        type_id: i32,
+        name: &str,
+        group_id: i32,
+        category_id: i32,
+        attributes: &[(i32, f64)],
+        effects: &[i32],
+    ) {
+        self.types.insert(
+            type_id,
+            TestType {
+                name: name.to_string(),
+                group_id,
+                category_id,
+                attributes: attributes.to_vec(),
+                effects: effects.to_vec(),
+            },
+        );
+    }
+
+    pub fn add_effect(&mut self, effect_id: i32, category: i32, modifiers: Vec<TestModifier>) {
+        self.effects.insert(
+            effect_id,
+            TestEffect {
+                category,
+                modifiers,
+            },
+        );
+    }
+}
+
+impl Info for TestInfo {
+    fn get_dogma_attributes(&self, type_id: i32) -> Vec<data_types::TypeDogmaAttribute> {
+        self.types
+            .get(&type_id)
+            .map(|type_| {
+                type_
+                    .attributes
+                    .iter()
+                    .map(|(attribute_id, value)| data_types::TypeDogmaAttribute {
+                        attributeID: *attribute_id,
+                        value: *value,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn get_dogma_attribute(&self, _attribute_id: i32) -> data_types::DogmaAttribute {
+        data_types::DogmaAttribute {
+            defaultValue: 0.0,
+            highIsGood: true,
+            stackable: true,
+        }
+    }
+
+    fn get_dogma_effects(&self, type_id: i32) -> Vec<data_types::TypeDogmaEffect> {
+        self.types
+            .get(&type_id)
+            .map(|type_| {
+                type_
+                    .effects
+                    .iter()
+                    .map(|effect_id| data_types::TypeDogmaEffect {
+                        effectID: *effect_id,
+                        isDefault: false,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn get_dogma_effect(&self, effect_id: i32) -> data_types::DogmaEffect {
+        let effect = self.effects.get(&effect_id);
+
+        data_types::DogmaEffect {
+            dischargeAttributeID: None,
+            durationAttributeID: None,
+            effectCategory: effect.map_or(0, |effect| effect.category),
+            electronicChance: false,
+            isAssistance: false,
+            isOffensive: false,
+            isWarpSafe: false,
+            propulsionChance: false,
+            rangeChance: false,
+            rangeAttributeID: None,
+            falloffAttributeID: None,
+            trackingSpeedAttributeID: None,
+            fittingUsageChanceAttributeID: None,
+            resistanceAttributeID: None,
+            modifierInfo: effect
+                .map(|effect| {
+                    effect
+                        .modifiers
+                        .iter()
+                        .map(|modifier| data_types::DogmaEffectModifierInfo {
+                            domain: modifier.domain.into(),
+                            func: modifier.func.into(),
+                            modifiedAttributeID: modifier.modified_attribute_id,
+                            modifyingAttributeID: modifier.modifying_attribute_id,
+                            operation: modifier.operation,
+                            groupID: None,
+                            skillTypeID: None,
+                            effectID: modifier.effect_id,
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+        }
+    }
+
+    fn get_type(&self, type_id: i32) -> data_types::Type {
+        let type_ = self.types.get(&type_id);
+
+        data_types::Type {
+            groupID: type_.map_or(0, |type_| type_.group_id),
+            categoryID: type_.map_or(0, |type_| type_.category_id),
+            published: type_.is_some(),
+            marketGroupID: None,
+            metaGroupID: None,
+            variationParentTypeID: None,
+            capacity: None,
+            mass: None,
+            radius: None,
+            volume: None,
+        }
+    }
+
+    fn attribute_name_to_id(&self, _name: &str) -> i32 {
+        0
+    }
+}
+
+impl InfoName for TestInfo {
+    fn type_name_to_id(&self, name: &str) -> i32 {
+        self.types
+            .iter()
+            .find(|(_, type_)| type_.name == name)
+            .map_or(0, |(type_id, _)| *type_id)
+    }
+
+    fn type_id_to_name(&self, type_id: i32) -> Option<String> {
+        self.types.get(&type_id).map(|type_| type_.name.clone())
+    }
+}
+
+/* A frigate with some modules for every slot type, a loadable charge, drones,
+ * an implant, a booster and a few items that only go in cargo. */
+pub fn test_universe() -> TestInfo {
+    let mut info = TestInfo::default();
+
+    info.add_type(587, "Rifter", 25, 6, &[], &[]);
+    info.add_type(47466, "Praxis", 419, 6, &[], &[]);
+    info.add_type(
+        484,
+        "125mm Gatling AutoCannon I",
+        55,
+        7,
+        &[
+            (ATTRIBUTE_CHARGE_GROUP1_ID, 83.0),
+            (ATTRIBUTE_CHARGE_SIZE_ID, 1.0),
+        ],
+        &[EFFECT_HI_POWER_ID],
+    );
+    info.add_type(185, "EMP S", 83, 8, &[(ATTRIBUTE_CHARGE_SIZE_ID, 1.0)], &[]);
+    info.add_type(
+        3831,
+        "Medium Shield Extender I",
+        38,
+        7,
+        &[],
+        &[EFFECT_MED_POWER_ID],
+    );
+    info.add_type(2048, "Damage Control II", 60, 7, &[], &[EFFECT_LO_POWER_ID]);
+    info.add_type(
+        1317,
+        "Expanded Cargohold I",
+        762,
+        7,
+        &[],
+        &[EFFECT_LO_POWER_ID],
+    );
+    info.add_type(
+        31788,
+        "Small Core Defense Field Extender I",
+        774,
+        7,
+        &[],
+        &[EFFECT_RIG_SLOT_ID],
+    );
+    info.add_type(
+        35892,
+        "Standup Market Hub I",
+        1415,
+        66,
+        &[],
+        &[EFFECT_SERVICE_SLOT_ID],
+    );
+    info.add_type(2486, "Warrior I", 100, 18, &[], &[]);
+    info.add_type(13283, "Limited Ocular Filter", 300, 20, &[], &[]);
+    info.add_type(28670, "Synth Blue Pill Booster", 303, 20, &[], &[]);
+    info.add_type(28668, "Nanite Repair Paste", 916, 8, &[], &[]);
+    info.add_type(34, "Tritanium", 18, 4, &[], &[]);
+
+    info
+}
+
+/* Everything of a fit and its cargo as sorted lines, so fits can be compared regardless of
+ * the order their items are listed in. Cargo of the same type is added up. */
+pub fn fit_lines(esf_fit: &data_types::EsfFit, cargo: &[data_types::EsfCargo]) -> Vec<String> {
+    let mut lines = vec![format!("ship {}", esf_fit.ship_type_id)];
+
+    for module in &esf_fit.modules {
+        lines.push(format!(
+            "module {:?} {} {} {:?} {:?}",
+            module.slot.r#type,
+            module.slot.index,
+            module.type_id,
+            module.state,
+            module.charge.as_ref().map(|charge| charge.type_id)
+        ));
+    }
+    for drone in &esf_fit.drones {
+        lines.push(format!("drone {} {:?}", drone.type_id, drone.state));
+    }
+    for implant in &esf_fit.implants {
+        lines.push(format!("implant {}", implant.type_id));
+    }
+    for booster in &esf_fit.boosters {
+        lines.push(format!("booster {}", booster.type_id));
+    }
+    if let Some(mode) = &esf_fit.mode {
+        lines.push(format!("mode {}", mode.type_id));
+    }
+
+    let mut quantities: Vec<(i32, i32)> = Vec::new();
+    for cargo in cargo {
+        match quantities
+            .iter_mut()
+            .find(|(type_id, _)| *type_id == cargo.type_id)
+        {
+            Some((_, quantity)) => *quantity += cargo.quantity,
+            None => quantities.push((cargo.type_id, cargo.quantity)),
+        }
+    }
+    for (type_id, quantity) in quantities {
+        lines.push(format!("cargo {} x{}", type_id, quantity));
+    }
+
+    lines.sort();
+    lines
+}
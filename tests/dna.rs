@@ -0,0 +1,57 @@
+#![cfg(feature = "dna")]
+
+mod common;
+
+use common::{fit_lines, test_universe};
+use esf_dogma_engine::dna;
+
+#[test]
+fn charges_that_are_not_loaded_go_to_cargo() {
+    let info = test_universe();
+    let dna_fit = dna::load_dna(&info, "587:484;2:185;100:28668;10:34_;5::").unwrap();
+
+    let modules = &dna_fit.esf_fit.modules;
+    assert_eq!(modules.len(), 2);
+    assert!(modules
+        .iter()
+        .all(|module| module.charge.as_ref().map(|charge| charge.type_id) == Some(185)));
+
+    let cargo: Vec<(i32, i32)> = dna_fit
+        .cargo
+        .iter()
+        .map(|cargo| (cargo.type_id, cargo.quantity))
+        .collect();
+    assert_eq!(cargo, vec![(34, 5), (185, 98), (28668, 10)]);
+}
+
+#[test]
+fn load_save_load_keeps_the_fit() {
+    let info = test_universe();
+    let dna_fit = dna::load_dna(
+        &info,
+        "587:2048;1:3831;1:484;2:31788;1:185;52:2486;2:13283;1:28670;1:34_;5::",
+    )
+    .unwrap();
+    let reloaded = dna::load_dna(&info, &dna::save_dna(&dna_fit.esf_fit, &dna_fit.cargo)).unwrap();
+
+    assert_eq!(
+        fit_lines(&reloaded.esf_fit, &reloaded.cargo),
+        fit_lines(&dna_fit.esf_fit, &dna_fit.cargo)
+    );
+}
+
+#[test]
+fn quantities_must_be_positive() {
+    let info = test_universe();
+
+    for (dna, entry) in [
+        ("587:484;-1::", "484;-1"),
+        ("587:484;1:34_;0::", "34_;0"),
+        ("587:34_;-5::", "34_;-5"),
+    ] {
+        assert_eq!(
+            dna::load_dna(&info, dna).err(),
+            Some(format!("Invalid quantity in DNA entry '{}'", entry))
+        );
+    }
+}
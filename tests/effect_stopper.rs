@@ -0,0 +1,158 @@
+mod common;
+
+use std::collections::BTreeMap;
+
+use common::{TestInfo, TestModifier, EFFECT_HI_POWER_ID, EFFECT_MED_POWER_ID};
+use esf_dogma_engine::calculate::item::{EffectCategory, Object};
+use esf_dogma_engine::calculate::{self, Input, Ship};
+use esf_dogma_engine::data_types::{EsfFit, EsfModule, EsfSlot, EsfSlotType, EsfState};
+
+const ATTRIBUTE_SHIELD_CAPACITY_ID: i32 = 263;
+const ATTRIBUTE_MAX_VELOCITY_ID: i32 = 37;
+const ATTRIBUTE_SHIELD_BONUS_ID: i32 = 1000;
+const ATTRIBUTE_VELOCITY_BONUS_ID: i32 = 1001;
+
+const EFFECT_SHIELD_BOOST_ID: i32 = 100;
+const EFFECT_VELOCITY_BOOST_ID: i32 = 101;
+const EFFECT_STOP_SHIELD_BOOST_ID: i32 = 102;
+
+const CATEGORY_ACTIVE: i32 = 1;
+const CATEGORY_ONLINE: i32 = 4;
+
+/* A module with an active shield boost and an online velocity boost, and a module
+ * that, when active, stops the shield boost of everything on the ship. */
+fn stopper_universe() -> TestInfo {
+    let mut info = TestInfo::default();
+
+    info.add_type(
+        1,
+        "Test Ship",
+        25,
+        6,
+        &[
+            (ATTRIBUTE_SHIELD_CAPACITY_ID, 1000.0),
+            (ATTRIBUTE_MAX_VELOCITY_ID, 300.0),
+        ],
+        &[],
+    );
+    info.add_type(
+        2,
+        "Test Booster",
+        38,
+        7,
+        &[
+            (ATTRIBUTE_SHIELD_BONUS_ID, 50.0),
+            (ATTRIBUTE_VELOCITY_BONUS_ID, 10.0),
+        ],
+        &[
+            EFFECT_MED_POWER_ID,
+            EFFECT_SHIELD_BOOST_ID,
+            EFFECT_VELOCITY_BOOST_ID,
+        ],
+    );
+    info.add_type(
+        3,
+        "Test Stopper",
+        515,
+        7,
+        &[(ATTRIBUTE_MAX_VELOCITY_ID, 0.0)],
+        &[EFFECT_HI_POWER_ID, EFFECT_STOP_SHIELD_BOOST_ID],
+    );
+
+    /* PostPercent on the ship's shield capacity. */
+    info.add_effect(
+        EFFECT_SHIELD_BOOST_ID,
+        CATEGORY_ACTIVE,
+        vec![TestModifier {
+            domain: 1,
+            func: 0,
+            modified_attribute_id: Some(ATTRIBUTE_SHIELD_CAPACITY_ID),
+            modifying_attribute_id: Some(ATTRIBUTE_SHIELD_BONUS_ID),
+            operation: Some(6),
+            effect_id: None,
+        }],
+    );
+    /* ModAdd on the ship's velocity. */
+    info.add_effect(
+        EFFECT_VELOCITY_BOOST_ID,
+        CATEGORY_ONLINE,
+        vec![TestModifier {
+            domain: 1,
+            func: 0,
+            modified_attribute_id: Some(ATTRIBUTE_MAX_VELOCITY_ID),
+            modifying_attribute_id: Some(ATTRIBUTE_VELOCITY_BONUS_ID),
+            operation: Some(2),
+            effect_id: None,
+        }],
+    );
+    info.add_effect(
+        EFFECT_STOP_SHIELD_BOOST_ID,
+        CATEGORY_ACTIVE,
+        vec![TestModifier {
+            domain: 1,
+            func: 5,
+            modified_attribute_id: None,
+            modifying_attribute_id: None,
+            operation: None,
+            effect_id: Some(EFFECT_SHIELD_BOOST_ID),
+        }],
+    );
+
+    info
+}
+
+fn calculate(stopper_state: EsfState) -> Ship {
+    let module = |type_id, slot_type, state| EsfModule {
+        type_id,
+        slot: EsfSlot {
+            r#type: slot_type,
+            index: 0,
+        },
+        state,
+        charge: None,
+    };
+
+    let fit = EsfFit {
+        ship_type_id: 1,
+        modules: vec![
+            module(2, EsfSlotType::Medium, EsfState::Active),
+            module(3, EsfSlotType::High, stopper_state),
+        ],
+        drones: vec![],
+        implants: vec![],
+        boosters: vec![],
+        mode: None,
+    };
+
+    calculate::calculate(&stopper_universe(), &Input::new(fit, BTreeMap::new()))
+}
+
+fn hull_value(ship: &Ship, attribute_id: i32) -> f64 {
+    ship.hull.attributes[&attribute_id].value.unwrap()
+}
+
+#[test]
+fn inactive_stopper_stops_nothing() {
+    let ship = calculate(EsfState::Online);
+
+    assert!(ship.stopped_effects.is_empty());
+    assert_eq!(hull_value(&ship, ATTRIBUTE_SHIELD_CAPACITY_ID), 1500.0);
+    assert_eq!(hull_value(&ship, ATTRIBUTE_MAX_VELOCITY_ID), 310.0);
+}
+
+#[test]
+fn active_stopper_stops_only_the_stopped_effect() {
+    let ship = calculate(EsfState::Active);
+
+    assert_eq!(ship.stopped_effects.len(), 1);
+    let stopped_effect = &ship.stopped_effects[0];
+    assert_eq!(stopped_effect.item, Object::Item(0));
+    assert_eq!(stopped_effect.source, Object::Item(1));
+    assert_eq!(stopped_effect.effect_id, EFFECT_SHIELD_BOOST_ID);
+    assert_eq!(stopped_effect.category, EffectCategory::Active);
+
+    /* The module stays active; its other effects still apply. */
+    assert_eq!(ship.items[0].state, EffectCategory::Active);
+    assert_eq!(hull_value(&ship, ATTRIBUTE_SHIELD_CAPACITY_ID), 1000.0);
+    assert_eq!(hull_value(&ship, ATTRIBUTE_MAX_VELOCITY_ID), 310.0);
+}
@@ -0,0 +1,220 @@
+#![cfg(feature = "eft")]
+
+mod common;
+
+use common::{fit_lines, test_universe};
+use esf_dogma_engine::data_types::EsfSlotType;
+use esf_dogma_engine::eft;
+
+#[test]
+fn implant_and_booster_lines_ignore_state_marker() {
+    let info = test_universe();
+    let eft_fit = eft::load_eft(
+        &info,
+        "[Rifter, Implants]\nDamage Control II /offline\n\nLimited Ocular Filter /offline\nSynth Blue Pill Booster /online\n",
+    )
+    .unwrap();
+
+    assert_eq!(eft_fit.esf_fit.modules.len(), 1);
+    assert_eq!(eft_fit.esf_fit.implants.len(), 1);
+    assert_eq!(eft_fit.esf_fit.implants[0].type_id, 13283);
+    assert_eq!(eft_fit.esf_fit.boosters.len(), 1);
+    assert_eq!(eft_fit.esf_fit.boosters[0].type_id, 28670);
+}
+
+const ROUND_TRIP_EFT: &str = "[Rifter, Round trip]
+[Empty Low slot]
+Damage Control II /offline
+
+Medium Shield Extender I
+
+125mm Gatling AutoCannon I, EMP S
+[Empty High slot]
+125mm Gatling AutoCannon I /overload
+
+Small Core Defense Field Extender I
+
+[Empty Service slot]
+Standup Market Hub I
+
+
+Warrior I x2
+
+Limited Ocular Filter
+
+Synth Blue Pill Booster
+
+Warrior I x3
+";
+
+#[test]
+fn parse_save_parse_keeps_the_fit() {
+    let info = test_universe();
+    let eft_fit = eft::load_eft(&info, ROUND_TRIP_EFT).unwrap();
+    let saved = eft::save_eft(&info, &eft_fit).unwrap();
+    let reloaded = eft::load_eft(&info, &saved).unwrap();
+
+    assert_eq!(
+        fit_lines(&reloaded.esf_fit, &reloaded.cargo),
+        fit_lines(&eft_fit.esf_fit, &eft_fit.cargo)
+    );
+    assert_eq!(eft::save_eft(&info, &reloaded).unwrap(), saved);
+}
+
+#[test]
+fn service_slot_indices_survive_a_round_trip() {
+    let info = test_universe();
+    let eft_fit = eft::load_eft(&info, ROUND_TRIP_EFT).unwrap();
+    let reloaded = eft::load_eft(&info, &eft::save_eft(&info, &eft_fit).unwrap()).unwrap();
+
+    let service = reloaded
+        .esf_fit
+        .modules
+        .iter()
+        .find(|module| module.slot.r#type == EsfSlotType::Service)
+        .unwrap();
+    assert_eq!(service.slot.index, 1);
+}
+
+#[test]
+fn cargo_with_only_drones_stays_cargo() {
+    let info = test_universe();
+    let eft_fit = eft::load_eft(&info, ROUND_TRIP_EFT).unwrap();
+    assert_eq!(eft_fit.esf_fit.drones.len(), 2);
+    assert_eq!(eft_fit.cargo.len(), 1);
+    assert_eq!(eft_fit.cargo[0].type_id, 2486);
+    assert_eq!(eft_fit.cargo[0].quantity, 3);
+
+    let reloaded = eft::load_eft(&info, &eft::save_eft(&info, &eft_fit).unwrap()).unwrap();
+    assert_eq!(reloaded.esf_fit.drones.len(), 2);
+    assert_eq!(reloaded.cargo.len(), 1);
+}
+
+#[test]
+fn names_containing_x_are_not_quantities() {
+    let info = test_universe();
+    let eft_fit = eft::load_eft(
+        &info,
+        "[Praxis, Cargo]\nExpanded Cargohold I\nExpanded Cargohold I\n\n\nTritanium x100\n",
+    )
+    .unwrap();
+
+    assert_eq!(eft_fit.esf_fit.ship_type_id, 47466);
+    assert_eq!(eft_fit.esf_fit.modules.len(), 2);
+    assert!(eft_fit
+        .esf_fit
+        .modules
+        .iter()
+        .all(|module| module.type_id == 1317));
+    assert_eq!(eft_fit.cargo.len(), 1);
+    assert_eq!(eft_fit.cargo[0].type_id, 34);
+    assert_eq!(eft_fit.cargo[0].quantity, 100);
+}
+
+#[test]
+fn unknown_lines_report_their_line_number() {
+    let info = test_universe();
+    let Err(diagnostics) = eft::parse_eft(
+        &info,
+        "[Rifter, Broken]\nDamage Control II\n[Empty Bogus slot]\nBogus Module I\n",
+        eft::EftMode::Strict,
+    ) else {
+        panic!("a broken fit should not parse");
+    };
+
+    let reported: Vec<String> = diagnostics.iter().map(|d| d.to_string()).collect();
+    assert_eq!(
+        reported,
+        vec![
+            "Line 3: Unknown empty slot '[Empty Bogus slot]'",
+            "Line 4: Unknown type 'Bogus Module I'",
+        ]
+    );
+    assert!(eft::load_eft(&info, "[Rifter, Broken]\nBogus Module I\n").is_err());
+}
+
+#[test]
+fn lenient_mode_skips_broken_lines() {
+    let info = test_universe();
+    let (eft_fit, diagnostics) = eft::parse_eft(
+        &info,
+        "[Rifter, Lenient]\nDamage Control II\nBogus Module I\nDamage Control II\n\n\nBogus Drone I x2\nWarrior I x2\n",
+        eft::EftMode::Lenient,
+    )
+    .unwrap();
+
+    assert_eq!(diagnostics.len(), 2);
+    assert_eq!(diagnostics[0].line, 3);
+    assert_eq!(diagnostics[1].line, 7);
+    assert_eq!(eft_fit.esf_fit.drones.len(), 2);
+
+    /* The skipped line keeps its slot, so the second module stays at index 2. */
+    let indices: Vec<(EsfSlotType, i32)> = eft_fit
+        .esf_fit
+        .modules
+        .iter()
+        .map(|module| (module.slot.r#type, module.slot.index))
+        .collect();
+    assert_eq!(indices, vec![(EsfSlotType::Low, 0), (EsfSlotType::Low, 2)]);
+}
+
+#[test]
+fn lenient_mode_keeps_slots_of_leading_broken_lines() {
+    let info = test_universe();
+    let (eft_fit, diagnostics) = eft::parse_eft(
+        &info,
+        "[Rifter, Lenient]\nBogus Module I\nDamage Control II\n[Empty Low slot]\nDamage Control II\n",
+        eft::EftMode::Lenient,
+    )
+    .unwrap();
+
+    assert_eq!(diagnostics.len(), 1);
+    let indices: Vec<i32> = eft_fit
+        .esf_fit
+        .modules
+        .iter()
+        .map(|module| module.slot.index)
+        .collect();
+    assert_eq!(indices, vec![1, 3]);
+}
+
+#[test]
+fn broken_fit_in_a_document_does_not_hide_the_others() {
+    let info = test_universe();
+    let results = eft::parse_eft_document(
+        &info,
+        "
+[Rifter, First]
+Damage Control II
+
+[Rifter, Broken]
+Damage Control II
+Bogus Module I
+
+[Praxis, Third]
+Expanded Cargohold I
+",
+        eft::EftMode::Strict,
+    );
+
+    assert_eq!(results.len(), 3);
+
+    let Ok((first, _)) = &results[0] else {
+        panic!("the first fit should parse");
+    };
+    assert_eq!(first.name, "First");
+    assert_eq!(first.esf_fit.modules[0].type_id, 2048);
+
+    let Err(diagnostics) = &results[1] else {
+        panic!("the second fit should not parse");
+    };
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].line, 7);
+
+    let Ok((third, _)) = &results[2] else {
+        panic!("the third fit should parse");
+    };
+    assert_eq!(third.name, "Third");
+    assert_eq!(third.esf_fit.ship_type_id, 47466);
+    assert_eq!(third.esf_fit.modules[0].type_id, 1317);
+}
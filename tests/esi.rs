@@ -0,0 +1,153 @@
+#![cfg(feature = "esi")]
+
+mod common;
+
+use esf_dogma_engine::data_types::{
+    EsfCargo, EsfCharge, EsfFit, EsfModule, EsfSlot, EsfSlotType, EsfState,
+};
+use esf_dogma_engine::esi::{self, EsiFitting};
+
+fn gun(index: i32) -> EsfModule {
+    EsfModule {
+        type_id: 484,
+        slot: EsfSlot {
+            r#type: EsfSlotType::High,
+            index,
+        },
+        state: EsfState::Active,
+        charge: Some(EsfCharge { type_id: 185 }),
+    }
+}
+
+#[test]
+fn loaded_charges_are_saved_as_cargo() {
+    let esf_fit = EsfFit {
+        ship_type_id: 587,
+        modules: vec![gun(0), gun(1)],
+        drones: vec![],
+        implants: vec![],
+        boosters: vec![],
+        mode: None,
+    };
+    let cargo = vec![EsfCargo {
+        type_id: 185,
+        quantity: 100,
+    }];
+
+    let esi_fitting = EsiFitting::from_esf_fit("Guns", "", &esf_fit, &cargo).unwrap();
+    let charges: Vec<_> = esi_fitting
+        .items
+        .iter()
+        .filter(|item| item.type_id == 185)
+        .collect();
+
+    assert_eq!(charges.len(), 1);
+    assert!(matches!(&charges[0].flag, esi::EsiFlag::Name(flag) if flag == "Cargo"));
+    assert_eq!(charges[0].quantity, 102);
+}
+
+#[test]
+fn slots_beyond_esi_flags_are_refused() {
+    let esf_fit = EsfFit {
+        ship_type_id: 587,
+        modules: vec![gun(7), gun(8)],
+        drones: vec![],
+        implants: vec![],
+        boosters: vec![],
+        mode: None,
+    };
+
+    let error = esi::save_esi_fitting("Guns", "", &esf_fit, &[]).unwrap_err();
+    assert!(error.contains("Slot index 8"));
+}
+
+#[test]
+fn unknown_flags_are_skipped() {
+    let esi_fit = esi::load_esi_fitting(
+        r#"{"name": "Carrier", "ship_type_id": 587, "items": [
+            {"type_id": 484, "flag": "HiSlot0", "quantity": 1},
+            {"type_id": 40556, "flag": "FighterBay", "quantity": 3}
+        ]}"#,
+    )
+    .unwrap();
+
+    assert_eq!(esi_fit.esf_fit.modules.len(), 1);
+    assert_eq!(esi_fit.skipped.len(), 1);
+    assert!(esi_fit.skipped[0].contains("FighterBay"));
+}
+
+#[test]
+fn killmail_items_in_other_holds_are_skipped() {
+    let info = common::test_universe();
+    let esi_fit = esi::load_killmail(
+        &info,
+        r#"{"killmail_id": 1, "victim": {"ship_type_id": 587, "items": [
+            {"item_type_id": 484, "flag": 27, "quantity_destroyed": 1},
+            {"item_type_id": 34, "flag": 155, "quantity_dropped": 1000}
+        ]}}"#,
+    )
+    .unwrap();
+
+    assert_eq!(esi_fit.esf_fit.modules.len(), 1);
+    assert!(esi_fit.cargo.is_empty());
+    assert_eq!(
+        esi_fit.skipped,
+        vec!["Skipped type 34: Unsupported inventory flag 155"]
+    );
+}
+
+#[test]
+fn killmail_charge_without_module_goes_to_cargo() {
+    let info = common::test_universe();
+    let esi_fit = esi::load_killmail(
+        &info,
+        r#"{"killmail_id": 1, "victim": {"ship_type_id": 587, "items": [
+            {"item_type_id": 484, "flag": 27, "quantity_destroyed": 1},
+            {"item_type_id": 185, "flag": 27, "quantity_destroyed": 1},
+            {"item_type_id": 185, "flag": 28, "quantity_dropped": 1},
+            {"item_type_id": 185, "flag": 5, "quantity_dropped": 50}
+        ]}}"#,
+    )
+    .unwrap();
+
+    assert_eq!(esi_fit.esf_fit.modules.len(), 1);
+    assert_eq!(
+        esi_fit.esf_fit.modules[0].charge.as_ref().unwrap().type_id,
+        185
+    );
+    assert_eq!(esi_fit.cargo.len(), 1);
+    assert_eq!(esi_fit.cargo[0].type_id, 185);
+    assert_eq!(esi_fit.cargo[0].quantity, 51);
+}
+
+#[test]
+fn load_save_load_keeps_the_fit() {
+    let esi_fit = esi::load_esi_fitting(
+        r#"{"name": "Round trip", "description": "Test", "ship_type_id": 587, "items": [
+            {"type_id": 2048, "flag": "LoSlot1", "quantity": 1},
+            {"type_id": 3831, "flag": "MedSlot0", "quantity": 1},
+            {"type_id": 484, "flag": "HiSlot0", "quantity": 1},
+            {"type_id": 484, "flag": "HiSlot2", "quantity": 1},
+            {"type_id": 31788, "flag": "RigSlot0", "quantity": 1},
+            {"type_id": 35892, "flag": "ServiceSlot1", "quantity": 1},
+            {"type_id": 2486, "flag": "DroneBay", "quantity": 2},
+            {"type_id": 185, "flag": "Cargo", "quantity": 100}
+        ]}"#,
+    )
+    .unwrap();
+    let saved = esi::save_esi_fitting(
+        &esi_fit.name,
+        &esi_fit.description,
+        &esi_fit.esf_fit,
+        &esi_fit.cargo,
+    )
+    .unwrap();
+    let reloaded = esi::load_esi_fitting(&saved).unwrap();
+
+    assert_eq!(reloaded.name, "Round trip");
+    assert_eq!(reloaded.description, "Test");
+    assert_eq!(
+        common::fit_lines(&reloaded.esf_fit, &reloaded.cargo),
+        common::fit_lines(&esi_fit.esf_fit, &esi_fit.cargo)
+    );
+}
@@ -0,0 +1,129 @@
+#![cfg(feature = "xml")]
+
+mod common;
+
+use common::{fit_lines, test_universe};
+use esf_dogma_engine::data_types::{
+    EsfCargo, EsfCharge, EsfFit, EsfModule, EsfSlot, EsfSlotType, EsfState,
+};
+use esf_dogma_engine::xml;
+
+#[test]
+fn broken_fitting_does_not_hide_the_others() {
+    let info = test_universe();
+    let fits = xml::load_xml(
+        &info,
+        r#"<?xml version="1.0" ?>
+<fittings>
+  <fitting name="Broken">
+    <shipType value="Unknown Ship"/>
+  </fitting>
+  <fitting name="Rifter">
+    <shipType value="Rifter"/>
+    <hardware slot="hi slot 0" type="125mm Gatling AutoCannon I"/>
+  </fitting>
+</fittings>"#,
+    );
+
+    assert_eq!(fits.len(), 2);
+    assert!(fits[0]
+        .as_ref()
+        .is_err_and(|e| e.starts_with("Fitting 'Broken'")));
+    let fit = fits[1].as_ref().unwrap();
+    assert_eq!(fit.esf_fit.ship_type_id, 587);
+    assert_eq!(fit.esf_fit.modules.len(), 1);
+}
+
+#[test]
+fn load_save_load_keeps_the_fit() {
+    let info = test_universe();
+    let xml_fits: Vec<xml::XmlFit> = xml::load_xml(
+        &info,
+        r#"<?xml version="1.0" ?>
+<fittings>
+  <fitting name="Round trip">
+    <description value="Test &amp; more"/>
+    <shipType value="Rifter"/>
+    <hardware slot="low slot 1" type="Damage Control II"/>
+    <hardware slot="med slot 0" type="Medium Shield Extender I"/>
+    <hardware slot="hi slot 0" type="125mm Gatling AutoCannon I"/>
+    <hardware slot="hi slot 2" type="125mm Gatling AutoCannon I"/>
+    <hardware slot="rig slot 0" type="Small Core Defense Field Extender I"/>
+    <hardware slot="service slot 1" type="Standup Market Hub I"/>
+    <hardware qty="2" slot="drone bay" type="Warrior I"/>
+    <hardware qty="100" slot="cargo" type="EMP S"/>
+  </fitting>
+</fittings>"#,
+    )
+    .into_iter()
+    .collect::<Result<_, _>>()
+    .unwrap();
+    let reloaded = xml::load_xml(&info, &xml::save_xml(&info, &xml_fits).unwrap());
+
+    assert_eq!(reloaded.len(), 1);
+    let reloaded = reloaded[0].as_ref().unwrap();
+    assert_eq!(reloaded.name, "Round trip");
+    assert_eq!(reloaded.description, "Test & more");
+    assert_eq!(
+        fit_lines(&reloaded.esf_fit, &reloaded.cargo),
+        fit_lines(&xml_fits[0].esf_fit, &xml_fits[0].cargo)
+    );
+}
+
+#[test]
+fn loaded_charges_are_saved_as_cargo() {
+    let info = test_universe();
+    let gun = |index| EsfModule {
+        type_id: 484,
+        slot: EsfSlot {
+            r#type: EsfSlotType::High,
+            index,
+        },
+        state: EsfState::Active,
+        charge: Some(EsfCharge { type_id: 185 }),
+    };
+    let xml_fit = xml::XmlFit {
+        name: "Guns".to_string(),
+        description: String::new(),
+        esf_fit: EsfFit {
+            ship_type_id: 587,
+            modules: vec![gun(0), gun(1)],
+            drones: vec![],
+            implants: vec![],
+            boosters: vec![],
+            mode: None,
+        },
+        cargo: vec![EsfCargo {
+            type_id: 185,
+            quantity: 100,
+        }],
+    };
+
+    let saved = xml::save_xml(&info, &[xml_fit]).unwrap();
+    assert!(saved.contains(r#"<hardware qty="102" slot="cargo" type="EMP S"/>"#));
+    assert_eq!(saved.matches("EMP S").count(), 1);
+}
+
+#[test]
+fn quantities_must_be_positive() {
+    let info = test_universe();
+
+    for quantity in ["0", "-2", "many"] {
+        let fits = xml::load_xml(
+            &info,
+            &format!(
+                r#"<fittings><fitting name="Rifter"><shipType value="Rifter"/>
+                <hardware qty="{}" slot="cargo" type="EMP S"/></fitting></fittings>"#,
+                quantity
+            ),
+        );
+
+        assert_eq!(
+            fits[0].as_ref().err(),
+            Some(&format!(
+                "Fitting 'Rifter': Invalid quantity '{}' for 'EMP S'",
+                quantity
+            ))
+        );
+    }
+}